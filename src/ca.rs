@@ -0,0 +1,502 @@
+use crate::cert::{self, KeyType};
+use crate::cli::{CaCommand, ProfileArgs};
+use crate::i18n::t;
+use crate::interactive::parse_key_type;
+use crate::key;
+use crate::pss;
+use crate::san;
+use anyhow::{bail, Context, Result};
+use console::Style;
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DnType, IsCa, KeyPair, KeyUsagePurpose,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::{Duration, OffsetDateTime};
+
+/// File name of the CA private key inside the CA home directory
+pub const CA_KEY_FILE: &str = "ca.key";
+
+/// File name of the CA certificate inside the CA home directory
+pub const CA_CERT_FILE: &str = "ca.crt";
+
+/// File name of an intermediate's chain (itself and its parents, without the root)
+pub const CA_CHAIN_FILE: &str = "chain.pem";
+
+/// Sub-directory of the CA home directory holding intermediate CAs
+pub const INTERMEDIATES_DIR: &str = "intermediates";
+
+/// File name of the CA profile (URLs for issued certificates) inside a CA directory
+pub const PROFILE_FILE: &str = "profile.json";
+
+pub struct CaOptions {
+    pub cn: String,
+    pub validity_days: i64,
+    pub organization: Option<String>,
+    pub key_type: KeyType,
+}
+
+/// A CA certificate and key that can sign other certificates
+pub struct LocalCa {
+    /// Certificate rebuilt from the CA parameters, used as the issuer when signing
+    pub cert: Certificate,
+    pub key_pair: KeyPair,
+    /// Signature scheme of the certificates, CRLs and OCSP responses this CA signs
+    pub key_type: KeyType,
+    /// The CA certificate exactly as stored on disk
+    pub cert_pem: String,
+    /// This CA and any intermediates above it, excluding the root (empty for a root CA)
+    pub chain_pem: Vec<String>,
+    /// URLs written into the certificates this CA issues
+    pub profile: CaProfile,
+}
+
+/// Where a CA publishes revocation information and its own certificate. Certificates
+/// it issues get these as Authority Information Access and CRL Distribution Points.
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct CaProfile {
+    /// OCSP responder (AIA id-ad-ocsp)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ocsp_url: Option<String>,
+    /// The CA certificate (AIA id-ad-caIssuers)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_issuers_url: Option<String>,
+    /// The CRL (CRL Distribution Points)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crl_url: Option<String>,
+    /// Signature scheme of the CA, recorded when it is created (missing for older CAs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_type: Option<KeyType>,
+}
+
+impl CaProfile {
+    pub fn is_empty(&self) -> bool {
+        self.ocsp_url.is_none()
+            && self.ca_issuers_url.is_none()
+            && self.crl_url.is_none()
+            && self.key_type.is_none()
+    }
+
+    /// Set the URLs given on the command line, keeping the others
+    fn update(&mut self, args: ProfileArgs) -> Result<()> {
+        for url in [&args.ocsp_url, &args.ca_issuers_url, &args.crl_url]
+            .into_iter()
+            .flatten()
+        {
+            san::check_url(url)?;
+        }
+        if args.ocsp_url.is_some() {
+            self.ocsp_url = args.ocsp_url;
+        }
+        if args.ca_issuers_url.is_some() {
+            self.ca_issuers_url = args.ca_issuers_url;
+        }
+        if args.crl_url.is_some() {
+            self.crl_url = args.crl_url;
+        }
+        Ok(())
+    }
+}
+
+impl LocalCa {
+    /// Common Name of the CA, for display
+    pub fn common_name(&self) -> String {
+        cert::common_name(&self.cert.params().distinguished_name)
+    }
+
+    /// Finish a certificate rcgen signed with this CA's key: RSA-PSS CAs re-sign
+    /// it, every other scheme is already signed correctly
+    pub fn resign(&self, der: &[u8]) -> Result<Vec<u8>> {
+        if pss::is_pss(self.key_type) {
+            pss::resign_certificate(der, &self.key_pair, self.key_type)
+        } else {
+            Ok(der.to_vec())
+        }
+    }
+}
+
+/// Default CA home directory (<data dir>/suresign/ca)
+pub fn default_ca_dir() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("suresign")
+        .join("ca")
+}
+
+/// Directory of the named intermediate CA
+pub fn intermediate_dir(ca_dir: &Path, name: &str) -> PathBuf {
+    ca_dir.join(INTERMEDIATES_DIR).join(name)
+}
+
+/// Build CA certificate parameters shared by root and intermediate CAs
+fn ca_params(opts: &CaOptions, constraints: BasicConstraints) -> Result<CertificateParams> {
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(DnType::CommonName, &opts.cn);
+    if let Some(o) = &opts.organization {
+        params.distinguished_name.push(DnType::OrganizationName, o);
+    }
+    params.is_ca = IsCa::Ca(constraints);
+    params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    params.serial_number = Some(cert::random_serial()?);
+
+    let now = OffsetDateTime::now_utc();
+    params.not_before = now;
+    params.not_after = now + Duration::days(opts.validity_days);
+
+    Ok(params)
+}
+
+/// Create a new self-signed root CA
+pub fn create_root(opts: CaOptions) -> Result<LocalCa> {
+    let key_pair = cert::generate_key_pair(opts.key_type, cert::DEFAULT_RSA_BITS)?;
+    let params = ca_params(&opts, BasicConstraints::Unconstrained)?;
+
+    let cert = params.self_signed(&key_pair)?;
+    let cert_pem = if pss::is_pss(opts.key_type) {
+        cert::to_pem(
+            "CERTIFICATE",
+            &pss::resign_certificate(cert.der(), &key_pair, opts.key_type)?,
+        )
+    } else {
+        cert.pem()
+    };
+
+    Ok(LocalCa {
+        cert,
+        key_pair,
+        key_type: opts.key_type,
+        cert_pem,
+        chain_pem: Vec::new(),
+        profile: CaProfile::default(),
+    })
+}
+
+/// Create an intermediate CA signed by `parent`, allowing `path_len` CAs below it
+/// (default: one less than a constrained parent allows, otherwise 0)
+pub fn create_intermediate(
+    parent: &LocalCa,
+    opts: CaOptions,
+    path_len: Option<u8>,
+) -> Result<LocalCa> {
+    let path_len = match parent.cert.params().is_ca {
+        IsCa::Ca(BasicConstraints::Constrained(0)) => {
+            bail!("The parent CA has a path length of 0 and cannot issue intermediate CAs")
+        }
+        IsCa::Ca(BasicConstraints::Constrained(parent_len)) => match path_len {
+            Some(len) if len >= parent_len => bail!(
+                "--path-len {} must be less than the parent CA's path length ({})",
+                len,
+                parent_len
+            ),
+            Some(len) => len,
+            None => parent_len - 1,
+        },
+        _ => path_len.unwrap_or(0),
+    };
+
+    let key_pair = cert::generate_key_pair(opts.key_type, cert::DEFAULT_RSA_BITS)?;
+    let mut params = ca_params(&opts, BasicConstraints::Constrained(path_len))?;
+    params.use_authority_key_identifier_extension = true;
+    cert::add_issuer_urls(&mut params, &parent.profile)?;
+
+    let cert = params.signed_by(&key_pair, &parent.cert, &parent.key_pair)?;
+    let cert_pem = cert::to_pem("CERTIFICATE", &parent.resign(cert.der())?);
+
+    let mut chain_pem = vec![cert_pem.clone()];
+    chain_pem.extend(parent.chain_pem.iter().cloned());
+
+    Ok(LocalCa {
+        cert,
+        key_pair,
+        key_type: opts.key_type,
+        cert_pem,
+        chain_pem,
+        profile: CaProfile::default(),
+    })
+}
+
+/// Load the CA stored in the given CA home directory
+pub fn load(dir: &Path) -> Result<LocalCa> {
+    let key_path = dir.join(CA_KEY_FILE);
+    let cert_path = dir.join(CA_CERT_FILE);
+
+    if !cert_path.exists() || !key_path.exists() {
+        bail!(
+            "No local CA found in {} (run `suresign ca init` first)",
+            dir.display()
+        );
+    }
+
+    let key_pem = fs::read_to_string(&key_path).context("Failed to read CA key")?;
+    let cert_pem = fs::read_to_string(&cert_path).context("Failed to read CA certificate")?;
+
+    let key_pair = KeyPair::from_pem(&key_pem).context("Failed to parse CA key")?;
+    let profile = load_profile(dir)?;
+    let mut key_type = key::key_type_of(&key_pair)?;
+    if let Some(recorded) = profile.key_type {
+        if !recorded.same_key_as(key_type) {
+            bail!(
+                "The CA profile records {:?}, which does not match the {:?} CA key",
+                recorded,
+                key_type
+            );
+        }
+        key_type = recorded;
+    } else if key_type.is_rsa() {
+        // A CA from before the scheme was recorded: use the scheme of its
+        // certificate's signature, which is its own for a root CA
+        let der = pem::parse(&cert_pem)
+            .context("Failed to parse CA certificate")?
+            .into_contents();
+        let (_, x509) = x509_parser::parse_x509_certificate(&der)
+            .map_err(|e| anyhow::anyhow!("Failed to parse CA certificate: {}", e))?;
+        if let Some(scheme) = cert::signature_key_type(&x509) {
+            key_type = scheme;
+        }
+    }
+    let key_pair = cert::with_key_type(&key_pair, key_type)?;
+    let params =
+        CertificateParams::from_ca_cert_pem(&cert_pem).context("Failed to parse CA certificate")?;
+    // Re-sign the parsed parameters so rcgen has an issuer to sign with.
+    // Only the subject and key identifier are used from it.
+    let cert = params.self_signed(&key_pair)?;
+
+    let chain_path = dir.join(CA_CHAIN_FILE);
+    let chain_pem = if chain_path.exists() {
+        let chain = fs::read_to_string(&chain_path).context("Failed to read CA chain")?;
+        pem::parse_many(&chain)
+            .context("Failed to parse CA chain")?
+            .iter()
+            .map(|block| cert::to_pem(block.tag(), block.contents()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    Ok(LocalCa {
+        cert,
+        key_pair,
+        key_type,
+        cert_pem,
+        chain_pem,
+        profile,
+    })
+}
+
+/// Load the profile of a CA directory (empty if none was set)
+pub fn load_profile(dir: &Path) -> Result<CaProfile> {
+    let path = dir.join(PROFILE_FILE);
+    if !path.exists() {
+        return Ok(CaProfile::default());
+    }
+    let content = fs::read_to_string(&path).context("Failed to read CA profile")?;
+    serde_json::from_str(&content).context("Failed to parse CA profile")
+}
+
+fn save_profile(dir: &Path, profile: &CaProfile) -> Result<()> {
+    let path = dir.join(PROFILE_FILE);
+    if profile.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).context("Failed to remove CA profile")?;
+        }
+        return Ok(());
+    }
+    let content = serde_json::to_string_pretty(profile)?;
+    fs::write(path, content + "\n").context("Failed to write CA profile")
+}
+
+/// Find the CA (root or intermediate) in the CA home directory whose subject
+/// matches the given DER-encoded issuer name
+pub fn find_issuer(dir: &Path, issuer_raw: &[u8]) -> Result<Option<LocalCa>> {
+    match find_issuer_dir(dir, issuer_raw) {
+        Some(found) => load(&found).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Directory of the CA (root or intermediate) whose subject matches the given
+/// DER-encoded issuer name
+pub fn find_issuer_dir(dir: &Path, issuer_raw: &[u8]) -> Option<PathBuf> {
+    let mut candidates = vec![dir.to_path_buf()];
+    if let Ok(entries) = fs::read_dir(dir.join(INTERMEDIATES_DIR)) {
+        candidates.extend(entries.filter_map(|e| e.ok()).map(|e| e.path()));
+    }
+
+    for candidate in candidates {
+        let Ok(cert_pem) = fs::read_to_string(candidate.join(CA_CERT_FILE)) else {
+            continue;
+        };
+        let Ok(block) = pem::parse(&cert_pem) else {
+            continue;
+        };
+        let Ok((_, x509)) = x509_parser::parse_x509_certificate(block.contents()) else {
+            continue;
+        };
+        if x509.subject().as_raw() == issuer_raw {
+            return Some(candidate);
+        }
+    }
+
+    None
+}
+
+/// Write the CA key and certificate to the CA home directory
+pub fn save(dir: &Path, ca: &LocalCa) -> Result<()> {
+    fs::create_dir_all(dir).context("Failed to create CA directory")?;
+    write_private(&dir.join(CA_KEY_FILE), &ca.key_pair.serialize_pem())
+        .context("Failed to write CA key")?;
+    fs::write(dir.join(CA_CERT_FILE), &ca.cert_pem).context("Failed to write CA certificate")?;
+    if !ca.chain_pem.is_empty() {
+        fs::write(dir.join(CA_CHAIN_FILE), ca.chain_pem.concat())
+            .context("Failed to write CA chain")?;
+    }
+    let profile = CaProfile {
+        key_type: Some(ca.key_type),
+        ..ca.profile.clone()
+    };
+    save_profile(dir, &profile)
+}
+
+/// Write a file readable only by the current user where the platform allows it
+pub fn write_private(path: &Path, contents: &str) -> Result<()> {
+    fs::write(path, contents)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+    }
+    Ok(())
+}
+
+/// Handle `suresign ca ...`
+pub fn run(action: CaCommand, dir: &Path, quiet: bool) -> Result<()> {
+    match action {
+        CaCommand::Init {
+            cn,
+            days,
+            org,
+            key_type,
+            force,
+            profile,
+        } => {
+            if dir.join(CA_CERT_FILE).exists() && !force {
+                bail!(
+                    "A CA already exists in {} (use --force to replace it)",
+                    dir.display()
+                );
+            }
+
+            let mut ca = create_root(CaOptions {
+                cn: cn.unwrap_or_else(|| "SureSign Local CA".to_string()),
+                validity_days: days.unwrap_or(3650),
+                organization: org,
+                key_type: key_type
+                    .as_deref()
+                    .map(parse_key_type)
+                    .unwrap_or(KeyType::Ecdsa),
+            })?;
+            ca.profile.update(profile)?;
+            save(dir, &ca)?;
+
+            if !quiet {
+                print_created(dir, &[CA_KEY_FILE, CA_CERT_FILE]);
+                println!("{}", t("ca_install_hint"));
+            }
+            Ok(())
+        }
+        CaCommand::Intermediate {
+            name,
+            cn,
+            days,
+            org,
+            key_type,
+            path_len,
+            parent,
+            force,
+            profile,
+        } => {
+            let target = intermediate_dir(dir, &name);
+            if target.join(CA_CERT_FILE).exists() && !force {
+                bail!(
+                    "Intermediate CA '{}' already exists (use --force to replace it)",
+                    name
+                );
+            }
+
+            let parent_ca = match &parent {
+                Some(p) => load(&intermediate_dir(dir, p))?,
+                None => load(dir)?,
+            };
+
+            let mut ca = create_intermediate(
+                &parent_ca,
+                CaOptions {
+                    cn: cn.unwrap_or_else(|| format!("SureSign Intermediate CA ({})", name)),
+                    validity_days: days.unwrap_or(1825),
+                    organization: org,
+                    key_type: key_type
+                        .as_deref()
+                        .map(parse_key_type)
+                        .unwrap_or(KeyType::Ecdsa),
+                },
+                path_len,
+            )?;
+            ca.profile.update(profile)?;
+            save(&target, &ca)?;
+
+            if !quiet {
+                print_created(&target, &[CA_KEY_FILE, CA_CERT_FILE, CA_CHAIN_FILE]);
+            }
+            Ok(())
+        }
+        CaCommand::Profile {
+            intermediate,
+            urls,
+            clear,
+        } => {
+            let target = match &intermediate {
+                Some(name) => intermediate_dir(dir, name),
+                None => dir.to_path_buf(),
+            };
+            if !target.join(CA_CERT_FILE).exists() {
+                bail!(
+                    "No local CA found in {} (run `suresign ca init` first)",
+                    target.display()
+                );
+            }
+
+            let mut profile = load_profile(&target)?;
+            if clear {
+                // Only the URLs; the signature scheme stays
+                profile = CaProfile {
+                    key_type: profile.key_type,
+                    ..CaProfile::default()
+                };
+            }
+            profile.update(urls)?;
+            save_profile(&target, &profile)?;
+
+            if !quiet {
+                let none = t("profile_none");
+                let show = |url: &Option<String>| url.clone().unwrap_or_else(|| none.clone());
+                println!("  OCSP: {}", show(&profile.ocsp_url));
+                println!("  CA Issuers: {}", show(&profile.ca_issuers_url));
+                println!("  CRL: {}", show(&profile.crl_url));
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_created(dir: &Path, files: &[&str]) {
+    let path_style = Style::new().cyan();
+    println!("{}", Style::new().green().bold().apply_to(t("ca_created")));
+    for file in files {
+        println!("  - {}", path_style.apply_to(dir.join(file).display()));
+    }
+}
@@ -1,20 +1,127 @@
+use crate::ca::{CaProfile, LocalCa};
+use crate::key;
+use crate::ocsp;
+use crate::pss;
+use crate::san::{self, parse_san};
+use crate::usage::{self, Purpose};
 use anyhow::Result;
-use rcgen::{CertificateParams, DnType, KeyPair, SanType};
-use std::net::IpAddr;
-use std::str::FromStr;
+use der::asn1::{Ia5String, ObjectIdentifier};
+use der::Encode;
+use rcgen::{
+    CertificateParams, CertificateSigningRequestParams, CrlDistributionPoint, CustomExtension,
+    DistinguishedName, DnType, DnValue, ExtendedKeyUsagePurpose, KeyPair, KeyUsagePurpose,
+    PublicKeyData, SanType, SerialNumber, SignatureAlgorithm,
+};
+use rsa::pkcs8::{EncodePrivateKey, LineEnding};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use time::{Duration, OffsetDateTime};
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::{AccessDescription, AuthorityInfoAccessSyntax};
+use x509_parser::signature_algorithm::SignatureAlgorithm as X509SignatureAlgorithm;
 
-#[derive(Debug, Clone, Copy)]
+/// PKCS#9 emailAddress attribute, used in client certificate subjects
+const EMAIL_ADDRESS_OID: [u64; 7] = [1, 2, 840, 113549, 1, 9, 1];
+
+/// id-pe-authorityInfoAccess
+const AUTHORITY_INFO_ACCESS_OID: [u64; 9] = [1, 3, 6, 1, 5, 5, 7, 1, 1];
+
+/// id-ce-cRLDistributionPoints
+const CRL_DISTRIBUTION_POINTS_OID: [u64; 4] = [2, 5, 29, 31];
+
+/// id-ad-caIssuers, the access method of the CA certificate URL
+const ID_AD_CA_ISSUERS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.48.2");
+
+/// RSA modulus sizes that can be generated
+pub const RSA_BITS: [usize; 3] = [2048, 3072, 4096];
+
+/// RSA modulus size used when none is given
+pub const DEFAULT_RSA_BITS: usize = 2048;
+
+/// Key algorithm and the signature scheme used when the key signs
+/// (serialized as its `--key-type` name)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum KeyType {
+    /// RSA, PKCS#1 v1.5 with SHA-256
     Rsa,
+    RsaSha384,
+    RsaSha512,
+    RsaPssSha256,
+    RsaPssSha384,
+    RsaPssSha512,
+    /// ECDSA P-256 with SHA-256
     Ecdsa,
+    /// ECDSA P-384 with SHA-384
+    EcdsaP384,
     Ed25519,
 }
 
+impl KeyType {
+    pub fn is_rsa(self) -> bool {
+        matches!(
+            self,
+            KeyType::Rsa
+                | KeyType::RsaSha384
+                | KeyType::RsaSha512
+                | KeyType::RsaPssSha256
+                | KeyType::RsaPssSha384
+                | KeyType::RsaPssSha512
+        )
+    }
+
+    /// Algorithm rcgen signs with. RSA-PSS types sign with PKCS#1 v1.5 first
+    /// and are re-signed afterwards (see `pss`).
+    pub fn signature_algorithm(self) -> &'static SignatureAlgorithm {
+        match self {
+            KeyType::Rsa
+            | KeyType::RsaPssSha256
+            | KeyType::RsaPssSha384
+            | KeyType::RsaPssSha512 => &rcgen::PKCS_RSA_SHA256,
+            KeyType::RsaSha384 => &rcgen::PKCS_RSA_SHA384,
+            KeyType::RsaSha512 => &rcgen::PKCS_RSA_SHA512,
+            KeyType::Ecdsa => &rcgen::PKCS_ECDSA_P256_SHA256,
+            KeyType::EcdsaP384 => &rcgen::PKCS_ECDSA_P384_SHA384,
+            KeyType::Ed25519 => &rcgen::PKCS_ED25519,
+        }
+    }
+
+    /// Whether both key types use the same kind of key (e.g. any two RSA types)
+    pub fn same_key_as(self, other: KeyType) -> bool {
+        if self.is_rsa() {
+            other.is_rsa()
+        } else {
+            self == other
+        }
+    }
+}
+
+/// Iteration count for PBKDF2 and the MAC in modern PFX files
+pub const DEFAULT_PFX_ITERATIONS: u32 = 10_000;
+
+/// Encryption used for the `.pfx` output
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PfxFormat {
+    /// PBES2 (PBKDF2-HMAC-SHA256, AES-256-CBC) with an HMAC-SHA256 MAC
+    Modern { iterations: u32 },
+    /// RC2/3DES with an HMAC-SHA1 MAC, for older Windows and Java versions
+    Legacy,
+}
+
+impl Default for PfxFormat {
+    fn default() -> Self {
+        PfxFormat::Modern {
+            iterations: DEFAULT_PFX_ITERATIONS,
+        }
+    }
+}
+
 pub struct CertOptions {
     // Simple
     pub cn: String,
     pub sans: Vec<String>,
+    // Add the apex domain for each wildcard SAN (*.dev.example → dev.example)
+    pub wildcard_apex: bool,
     pub validity_days: i64,
     // Full
     pub country: Option<String>,
@@ -22,28 +129,353 @@ pub struct CertOptions {
     pub city: Option<String>,
     pub organization: Option<String>,
     pub org_unit: Option<String>,
+    // Client identity: subject emailAddress / email SAN, and URI SAN
+    pub email: Option<String>,
+    pub uri: Option<String>,
     // All
     pub key_type: KeyType,
+    // Modulus size when key_type is RSA
+    pub rsa_bits: usize,
+    // Certificate purpose, deciding the default key usages
+    pub purpose: Purpose,
+    // Key Usage and Extended Key Usage (None = purpose defaults)
+    pub key_usages: Option<Vec<KeyUsagePurpose>>,
+    pub extended_key_usages: Option<Vec<ExtendedKeyUsagePurpose>>,
+    // Authority Information Access and CRL Distribution Points URLs
+    // (None = the issuing CA's profile)
+    pub ocsp_url: Option<String>,
+    pub ca_issuers_url: Option<String>,
+    pub crl_url: Option<String>,
     // New: PFX password
     pub pfx_password: String,
+    // PFX encryption scheme
+    pub pfx_format: PfxFormat,
+    // CA to sign with (None = self-signed)
+    pub issuer: Option<LocalCa>,
+    // Existing key to certify (None = generate a new one)
+    pub existing_key: Option<KeyPair>,
+}
+
+/// The `--default_settings` certificate: `localhost` and `127.0.0.1`, one year, ECDSA P-256
+impl Default for CertOptions {
+    fn default() -> Self {
+        CertOptions {
+            cn: "localhost".to_string(),
+            sans: vec!["127.0.0.1".to_string()],
+            wildcard_apex: false,
+            validity_days: 365,
+            country: None,
+            state: None,
+            city: None,
+            organization: None,
+            org_unit: None,
+            email: None,
+            uri: None,
+            key_type: KeyType::Ecdsa,
+            rsa_bits: DEFAULT_RSA_BITS,
+            purpose: Purpose::default(),
+            key_usages: None,
+            extended_key_usages: None,
+            ocsp_url: None,
+            ca_issuers_url: None,
+            crl_url: None,
+            pfx_password: String::new(),
+            pfx_format: PfxFormat::default(),
+            issuer: None,
+            existing_key: None,
+        }
+    }
 }
 
 pub struct GeneratedCert {
     pub cert_pem: String,
     pub key_pem: String,
+    // DER certificate and PKCS#8 DER key, for binary outputs
+    pub cert_der: Vec<u8>,
+    pub key_der: Vec<u8>,
     pub pfx: Vec<u8>,
+    // Intermediate CA certificates above the leaf, issuer first (empty if none)
+    pub chain_pem: Vec<String>,
+}
+
+/// Generate a new key pair for the given key type
+pub fn generate_key_pair(key_type: KeyType, rsa_bits: usize) -> Result<KeyPair> {
+    if key_type.is_rsa() {
+        return generate_rsa_key_pair(rsa_bits, key_type.signature_algorithm());
+    }
+    Ok(KeyPair::generate_for(key_type.signature_algorithm())?)
+}
+
+/// Switch a key pair to the signature algorithm of the given key type (same key)
+pub fn with_key_type(key_pair: &KeyPair, key_type: KeyType) -> Result<KeyPair> {
+    Ok(KeyPair::from_pkcs8_pem_and_sign_algo(
+        &key_pair.serialize_pem(),
+        key_type.signature_algorithm(),
+    )?)
+}
+
+/// Generate an RSA key pair. rcgen's ring backend cannot generate RSA keys,
+/// so the key is generated with the `rsa` crate and imported as PKCS#8.
+fn generate_rsa_key_pair(bits: usize, alg: &'static SignatureAlgorithm) -> Result<KeyPair> {
+    if !RSA_BITS.contains(&bits) {
+        anyhow::bail!(
+            "Unsupported RSA key size {} (use one of {:?})",
+            bits,
+            RSA_BITS
+        );
+    }
+    let private_key = rsa::RsaPrivateKey::new(&mut rsa::rand_core::OsRng, bits)
+        .map_err(|e| anyhow::anyhow!("Failed to generate RSA key: {}", e))?;
+    let pem = private_key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(|e| anyhow::anyhow!("Failed to encode RSA key: {}", e))?;
+    Ok(KeyPair::from_pkcs8_pem_and_sign_algo(&pem, alg)?)
+}
+
+/// Generate a random positive 16-byte serial number
+pub fn random_serial() -> Result<SerialNumber> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| anyhow::anyhow!("Failed to generate serial number: {}", e))?;
+    // Clear the top bit so the DER INTEGER stays positive
+    bytes[0] &= 0x7f;
+    Ok(SerialNumber::from_slice(&bytes))
+}
+
+pub struct GeneratedCsr {
+    pub csr_pem: String,
+    pub key_pem: String,
+    pub key_der: Vec<u8>,
+}
+
+/// Options for issuing a certificate from a CSR
+pub struct SignOptions {
+    pub validity_days: i64,
+    // Replaces the SANs requested in the CSR when set
+    pub sans: Option<Vec<String>>,
+    pub purpose: Purpose,
+    // Key usages replacing the purpose defaults; the CSR's requested usages are never copied
+    pub key_usages: Option<Vec<KeyUsagePurpose>>,
+    pub extended_key_usages: Option<Vec<ExtendedKeyUsagePurpose>>,
+}
+
+impl SignOptions {
+    /// Key Usage and Extended Key Usage to issue for a CSR
+    pub fn usages(&self, csr: &ParsedCsr) -> (Vec<KeyUsagePurpose>, Vec<ExtendedKeyUsagePurpose>) {
+        let key_usages = self
+            .key_usages
+            .clone()
+            .unwrap_or_else(|| usage::default_key_usages(self.purpose, csr.public_key.key_type));
+        let extended_key_usages = self
+            .extended_key_usages
+            .clone()
+            .unwrap_or_else(|| usage::default_extended_key_usages(self.purpose));
+        (key_usages, extended_key_usages)
+    }
+}
+
+/// A certificate signing request whose signature has been verified
+pub struct ParsedCsr {
+    pub params: CertificateParams,
+    pub public_key: CsrPublicKey,
+}
+
+/// Public key of a CSR, as it goes into the issued certificate
+pub struct CsrPublicKey {
+    der: Vec<u8>,
+    pub key_type: KeyType,
+}
+
+impl PublicKeyData for CsrPublicKey {
+    fn der_bytes(&self) -> &[u8] {
+        &self.der
+    }
+
+    fn algorithm(&self) -> &SignatureAlgorithm {
+        self.key_type.signature_algorithm()
+    }
+}
+
+/// A certificate issued for a key we do not hold
+pub struct IssuedCert {
+    pub cert_pem: String,
+    pub cert_der: Vec<u8>,
+    pub chain_pem: Vec<String>,
+}
+
+/// Common Name of a distinguished name, or an empty string
+pub fn common_name(dn: &DistinguishedName) -> String {
+    match dn.get(&DnType::CommonName) {
+        Some(DnValue::Utf8String(s)) => s.clone(),
+        Some(DnValue::PrintableString(s)) => s.as_str().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Format a distinguished name for display (e.g. "CN=foo, O=bar")
+pub fn dn_to_string(dn: &DistinguishedName) -> String {
+    dn.iter()
+        .map(|(ty, value)| {
+            let label = match ty {
+                DnType::CountryName => "C",
+                DnType::StateOrProvinceName => "ST",
+                DnType::LocalityName => "L",
+                DnType::OrganizationName => "O",
+                DnType::OrganizationalUnitName => "OU",
+                DnType::CommonName => "CN",
+                DnType::CustomDnType(oid) if oid[..] == EMAIL_ADDRESS_OID => "emailAddress",
+                _ => "?",
+            };
+            let value = match value {
+                DnValue::Utf8String(s) => s.clone(),
+                DnValue::PrintableString(s) => s.as_str().to_string(),
+                DnValue::Ia5String(s) => s.as_str().to_string(),
+                _ => "(unsupported)".to_string(),
+            };
+            format!("{}={}", label, value)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// PEM-encode DER data with LF line endings, as rcgen does
+pub fn to_pem(tag: &str, der: &[u8]) -> String {
+    let config = pem::EncodeConfig::new().set_line_ending(pem::LineEnding::LF);
+    pem::encode_config(&pem::Pem::new(tag, der), config)
 }
 
-pub fn generate_cert(opt: CertOptions) -> Result<GeneratedCert> {
-    // Generate key pair based on key type
-    let key_pair = match opt.key_type {
-        KeyType::Rsa => KeyPair::generate_for(&rcgen::PKCS_RSA_SHA256)?,
-        KeyType::Ecdsa => KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256)?,
-        KeyType::Ed25519 => KeyPair::generate_for(&rcgen::PKCS_ED25519)?,
+/// Parse a PKCS#10 certificate signing request (PEM or DER) and verify its signature
+pub fn parse_csr(data: &[u8]) -> Result<ParsedCsr> {
+    let der = match std::str::from_utf8(data) {
+        Ok(text) if text.contains("-----BEGIN") => pem::parse(text)?.into_contents(),
+        _ => data.to_vec(),
     };
 
-    // Create certificate params
-    let mut params = CertificateParams::new(vec![opt.cn.clone()])?;
+    // rcgen cannot verify RSA-PSS signatures, so those are checked with the rsa crate
+    if pss::is_pss_csr(&der) {
+        pss::verify_csr(&der)?;
+        return pss_csr(&der);
+    }
+
+    let csr = CertificateSigningRequestParams::from_der(&der.into())?;
+    Ok(ParsedCsr {
+        public_key: CsrPublicKey {
+            der: csr.public_key.der_bytes().to_vec(),
+            key_type: key::key_type_of_algorithm(csr.public_key.algorithm())?,
+        },
+        params: csr.params,
+    })
+}
+
+/// Subject and SANs of an RSA-PSS CSR whose signature was already verified.
+/// The requested key usages are left out, as `sign_csr` never copies them.
+fn pss_csr(der: &[u8]) -> Result<ParsedCsr> {
+    use x509_parser::certification_request::X509CertificationRequest;
+    use x509_parser::der_parser::asn1_rs::Tag;
+    use x509_parser::extensions::{GeneralName as X509GeneralName, ParsedExtension};
+    use x509_parser::prelude::FromDer;
+
+    let (_, csr) = X509CertificationRequest::from_der(der)
+        .map_err(|e| anyhow::anyhow!("Failed to parse CSR: {}", e))?;
+    let info = &csr.certification_request_info;
+
+    let mut params = CertificateParams::default();
+    for attr in info.subject.iter_attributes() {
+        let oid: Vec<u64> = attr
+            .attr_type()
+            .iter()
+            .ok_or_else(|| anyhow::anyhow!("Unsupported attribute in CSR subject"))?
+            .collect();
+        let value = attr
+            .as_str()
+            .map_err(|e| anyhow::anyhow!("Unsupported value in CSR subject: {}", e))?;
+        let value = match attr.attr_value().header.tag() {
+            Tag::PrintableString => DnValue::PrintableString(value.try_into()?),
+            Tag::Ia5String => DnValue::Ia5String(value.try_into()?),
+            _ => DnValue::Utf8String(value.to_string()),
+        };
+        params
+            .distinguished_name
+            .push(DnType::from_oid(&oid), value);
+    }
+
+    for ext in csr.requested_extensions().into_iter().flatten() {
+        if let ParsedExtension::SubjectAlternativeName(san) = ext {
+            for name in &san.general_names {
+                let san = match name {
+                    X509GeneralName::DNSName(name) => SanType::DnsName((*name).try_into()?),
+                    X509GeneralName::RFC822Name(name) => SanType::Rfc822Name((*name).try_into()?),
+                    X509GeneralName::URI(name) => SanType::URI((*name).try_into()?),
+                    X509GeneralName::IPAddress(bytes) => SanType::IpAddress(match bytes.len() {
+                        4 => std::net::IpAddr::from(<[u8; 4]>::try_from(*bytes)?),
+                        16 => std::net::IpAddr::from(<[u8; 16]>::try_from(*bytes)?),
+                        _ => anyhow::bail!("Invalid IP address SAN in CSR"),
+                    }),
+                    other => anyhow::bail!("Unsupported SAN in RSA-PSS CSR: {}", other),
+                };
+                params.subject_alt_names.push(san);
+            }
+        }
+    }
+
+    Ok(ParsedCsr {
+        params,
+        public_key: CsrPublicKey {
+            der: info.subject_pki.subject_public_key.data.to_vec(),
+            key_type: KeyType::Rsa,
+        },
+    })
+}
+
+/// Issue a certificate for a CSR from the given CA
+pub fn sign_csr(mut csr: ParsedCsr, opt: SignOptions, ca: &LocalCa) -> Result<IssuedCert> {
+    if let Some(sans) = &opt.sans {
+        csr.params.subject_alt_names = sans
+            .iter()
+            .map(|s| parse_san(s))
+            .collect::<Result<Vec<_>>>()?;
+    }
+    let (key_usages, extended_key_usages) = opt.usages(&csr);
+    csr.params.key_usages = key_usages;
+    csr.params.extended_key_usages = extended_key_usages;
+
+    let now = OffsetDateTime::now_utc();
+    csr.params.not_before = now;
+    csr.params.not_after = now + Duration::days(opt.validity_days);
+    csr.params.serial_number = Some(random_serial()?);
+    csr.params.use_authority_key_identifier_extension = true;
+    add_issuer_urls(&mut csr.params, &ca.profile)?;
+
+    let cert = csr
+        .params
+        .signed_by(&csr.public_key, &ca.cert, &ca.key_pair)?;
+    let cert_der = ca.resign(cert.der())?;
+
+    Ok(IssuedCert {
+        cert_pem: to_pem("CERTIFICATE", &cert_der),
+        cert_der,
+        chain_pem: ca.chain_pem.clone(),
+    })
+}
+
+/// Build certificate parameters (subject, SANs, validity, key usages) from the options
+pub fn build_params(opt: &CertOptions) -> Result<CertificateParams> {
+    // Create certificate params. Only server certificates name a host in the CN;
+    // client and signing certificates have no DNS SAN unless one is given.
+    // An internationalized host name is stored as its A-label, in the CN and SAN alike.
+    let mut params = CertificateParams::default();
+    let cn = if opt.purpose == Purpose::Server {
+        let san = san::parse_host_cn(&opt.cn)?;
+        let cn = match &san {
+            SanType::DnsName(name) => name.as_str().to_string(),
+            _ => opt.cn.trim().to_string(),
+        };
+        params.subject_alt_names.push(san);
+        cn
+    } else {
+        opt.cn.clone()
+    };
+    params.distinguished_name.push(DnType::CommonName, &cn);
 
     // Set validity
     let now = OffsetDateTime::now_utc();
@@ -53,14 +485,15 @@ pub fn generate_cert(opt: CertOptions) -> Result<GeneratedCert> {
 
     // Set SANs
     for san in &opt.sans {
-        if let Ok(ip) = IpAddr::from_str(san) {
-            params.subject_alt_names.push(SanType::IpAddress(ip));
-        } else {
-            params
-                .subject_alt_names
-                .push(SanType::DnsName(san.clone().try_into()?));
+        // The CN may already be there (e.g. a Unicode CN and SAN for the same name)
+        let san = parse_san(san)?;
+        if !params.subject_alt_names.contains(&san) {
+            params.subject_alt_names.push(san);
         }
     }
+    if opt.wildcard_apex {
+        add_wildcard_apexes(&mut params.subject_alt_names)?;
+    }
 
     // Set DN fields (Full Mode)
     if let Some(c) = &opt.country {
@@ -83,55 +516,358 @@ pub fn generate_cert(opt: CertOptions) -> Result<GeneratedCert> {
             .push(DnType::OrganizationalUnitName, ou);
     }
 
-    // Generate self-signed certificate
-    let cert = params.self_signed(&key_pair)?;
-    let cert_pem = cert.pem();
+    // Client identity
+    if let Some(email) = &opt.email {
+        params.distinguished_name.push(
+            DnType::CustomDnType(EMAIL_ADDRESS_OID.to_vec()),
+            DnValue::Ia5String(email.as_str().try_into()?),
+        );
+        params
+            .subject_alt_names
+            .push(parse_san(&format!("email:{}", email))?);
+    }
+    if let Some(uri) = &opt.uri {
+        params
+            .subject_alt_names
+            .push(parse_san(&format!("uri:{}", uri))?);
+    }
+
+    // Set Key Usage / Extended Key Usage
+    params.key_usages = opt
+        .key_usages
+        .clone()
+        .unwrap_or_else(|| usage::default_key_usages(opt.purpose, opt.key_type));
+    params.extended_key_usages = opt
+        .extended_key_usages
+        .clone()
+        .unwrap_or_else(|| usage::default_extended_key_usages(opt.purpose));
+
+    // Revocation and issuer URLs, defaulting to the issuing CA's profile
+    let profile = opt
+        .issuer
+        .as_ref()
+        .map(|ca| ca.profile.clone())
+        .unwrap_or_default();
+    add_issuer_urls(
+        &mut params,
+        &CaProfile {
+            ocsp_url: opt.ocsp_url.clone().or(profile.ocsp_url),
+            ca_issuers_url: opt.ca_issuers_url.clone().or(profile.ca_issuers_url),
+            crl_url: opt.crl_url.clone().or(profile.crl_url),
+            key_type: None,
+        },
+    )?;
+
+    Ok(params)
+}
+
+/// Add Authority Information Access (OCSP, CA Issuers) and CRL Distribution Points
+pub fn add_issuer_urls(params: &mut CertificateParams, urls: &CaProfile) -> Result<()> {
+    let mut access = Vec::new();
+    if let Some(url) = &urls.ocsp_url {
+        access.push((ocsp::ID_AD_OCSP, url.as_str()));
+    }
+    if let Some(url) = &urls.ca_issuers_url {
+        access.push((ID_AD_CA_ISSUERS, url.as_str()));
+    }
+    if !access.is_empty() {
+        params
+            .custom_extensions
+            .push(authority_info_access(&access)?);
+    }
+    if let Some(url) = &urls.crl_url {
+        san::check_url(url)?;
+        params.crl_distribution_points = vec![CrlDistributionPoint {
+            uris: vec![url.clone()],
+        }];
+    }
+    Ok(())
+}
+
+/// Authority Information Access extension (RFC 5280 §4.2.2.1) with one URL per access method
+fn authority_info_access(access: &[(ObjectIdentifier, &str)]) -> Result<CustomExtension> {
+    let descriptions = access
+        .iter()
+        .map(|(method, url)| {
+            san::check_url(url)?;
+            let location =
+                Ia5String::new(url).map_err(|e| anyhow::anyhow!("Invalid URL '{}': {}", url, e))?;
+            Ok(AccessDescription {
+                access_method: *method,
+                access_location: GeneralName::UniformResourceIdentifier(location),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let der = AuthorityInfoAccessSyntax(descriptions)
+        .to_der()
+        .map_err(|e| anyhow::anyhow!("Failed to encode Authority Information Access: {}", e))?;
+    Ok(CustomExtension::from_oid_content(
+        &AUTHORITY_INFO_ACCESS_OID,
+        der,
+    ))
+}
+
+/// Insert the apex domain after each wildcard DNS name that lacks one
+fn add_wildcard_apexes(sans: &mut Vec<SanType>) -> Result<()> {
+    let mut with_apexes = Vec::with_capacity(sans.len());
+    for san in sans.iter() {
+        with_apexes.push(san.clone());
+        if let SanType::DnsName(name) = san {
+            if let Some(base) = san::wildcard_base(name.as_str()) {
+                let apex = SanType::DnsName(base.try_into()?);
+                if !sans.contains(&apex) && !with_apexes.contains(&apex) {
+                    with_apexes.push(apex);
+                }
+            }
+        }
+    }
+    *sans = with_apexes;
+    Ok(())
+}
+
+pub fn generate_cert(mut opt: CertOptions) -> Result<GeneratedCert> {
+    // Use the supplied key or generate one based on key type
+    let key_pair = match opt.existing_key.take() {
+        Some(key_pair) => key_pair,
+        None => generate_key_pair(opt.key_type, opt.rsa_bits)?,
+    };
+    let params = build_params(&opt)?;
+
+    sign_and_bundle(
+        params,
+        &key_pair,
+        opt.key_type,
+        opt.issuer.as_ref(),
+        &opt.pfx_password,
+        opt.pfx_format,
+        &opt.cn,
+    )
+}
+
+/// Key type of a certificate's signature scheme (RSA variants only)
+pub fn signature_key_type(x509: &x509_parser::certificate::X509Certificate) -> Option<KeyType> {
+    let oid = x509.signature_algorithm.algorithm.to_id_string();
+    match oid.as_str() {
+        "1.2.840.113549.1.1.11" => Some(KeyType::Rsa),
+        "1.2.840.113549.1.1.12" => Some(KeyType::RsaSha384),
+        "1.2.840.113549.1.1.13" => Some(KeyType::RsaSha512),
+        "1.2.840.113549.1.1.10" => {
+            // The hash is carried in the RSASSA-PSS parameters
+            let Ok(X509SignatureAlgorithm::RSASSA_PSS(params)) =
+                X509SignatureAlgorithm::try_from(&x509.signature_algorithm)
+            else {
+                return None;
+            };
+            match params.hash_algorithm_oid().to_id_string().as_str() {
+                "2.16.840.1.101.3.4.2.2" => Some(KeyType::RsaPssSha384),
+                "2.16.840.1.101.3.4.2.3" => Some(KeyType::RsaPssSha512),
+                _ => Some(KeyType::RsaPssSha256),
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Re-issue an existing certificate for its existing key with a new validity period.
+/// Subject, SANs and extensions are copied from the old certificate.
+pub fn renew_cert(
+    cert_pem: &str,
+    key_pair: &KeyPair,
+    validity_days: Option<i64>,
+    issuer: Option<&LocalCa>,
+    pfx_password: &str,
+    pfx_format: PfxFormat,
+) -> Result<GeneratedCert> {
+    let mut params = CertificateParams::from_ca_cert_pem(cert_pem)?;
+
+    // Make sure the key on disk belongs to this certificate
+    let cert_der = pem::parse(cert_pem)?.into_contents();
+    let (_, x509) = x509_parser::parse_x509_certificate(&cert_der)
+        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+    if x509.public_key().raw != key_pair.public_key_der().as_slice() {
+        anyhow::bail!("The private key does not match the certificate");
+    }
+
+    // Default to the same validity period as the old certificate
+    let days = validity_days.unwrap_or_else(|| (params.not_after - params.not_before).whole_days());
+    let now = OffsetDateTime::now_utc();
+    params.not_before = now;
+    params.not_after = now + Duration::days(days);
+    params.serial_number = Some(random_serial()?);
+
+    // rcgen does not read these back, so copy them over as they were
+    for ext in x509.extensions() {
+        let oid: Vec<u64> = ext
+            .oid
+            .iter()
+            .map(|arcs| arcs.collect())
+            .unwrap_or_default();
+        if oid == AUTHORITY_INFO_ACCESS_OID || oid == CRL_DISTRIBUTION_POINTS_OID {
+            params
+                .custom_extensions
+                .push(CustomExtension::from_oid_content(&oid, ext.value.to_vec()));
+        }
+    }
+
+    let cn = common_name(&params.distinguished_name);
+    // Self-signed certificates keep their signature scheme (e.g. RSA-PSS)
+    let self_signed = x509.subject().as_raw() == x509.issuer().as_raw();
+    let key_type = match signature_key_type(&x509).filter(|_| self_signed) {
+        Some(key_type) => key_type,
+        None => crate::key::key_type_of(key_pair)?,
+    };
+    let key_pair = with_key_type(key_pair, key_type)?;
+    sign_and_bundle(
+        params,
+        &key_pair,
+        key_type,
+        issuer,
+        pfx_password,
+        pfx_format,
+        &cn,
+    )
+}
+
+/// Sign the parameters (with the CA, or self-signed) and build the PEM and PFX outputs
+fn sign_and_bundle(
+    mut params: CertificateParams,
+    key_pair: &KeyPair,
+    key_type: KeyType,
+    issuer: Option<&LocalCa>,
+    pfx_password: &str,
+    pfx_format: PfxFormat,
+    friendly_name: &str,
+) -> Result<GeneratedCert> {
+    // Sign with the local CA if one was given, otherwise self-sign
+    let cert = match issuer {
+        Some(ca) => {
+            if params.serial_number.is_none() {
+                params.serial_number = Some(random_serial()?);
+            }
+            params.use_authority_key_identifier_extension = true;
+            params.signed_by(key_pair, &ca.cert, &ca.key_pair)?
+        }
+        None => params.self_signed(key_pair)?,
+    };
+    let (cert_der, cert_pem) = match issuer {
+        Some(ca) => {
+            let der = ca.resign(cert.der())?;
+            let pem = to_pem("CERTIFICATE", &der);
+            (der, pem)
+        }
+        None if pss::is_pss(key_type) => {
+            let der = pss::resign_certificate(cert.der(), key_pair, key_type)?;
+            let pem = to_pem("CERTIFICATE", &der);
+            (der, pem)
+        }
+        None => (cert.der().to_vec(), cert.pem()),
+    };
     let key_pem = key_pair.serialize_pem();
 
     // Generate PFX
-    let cert_der = cert.der().as_ref();
     let key_der = key_pair.serialize_der();
 
-    let pfx_password: Option<&[u8]> = if opt.pfx_password.is_empty() {
-        None
-    } else {
-        Some(opt.pfx_password.as_bytes())
-    };
-
-    let pfx = p12::PFX::new(cert_der, &key_der, pfx_password, "", &opt.cn)
-        .ok_or_else(|| anyhow::anyhow!("Failed to generate PFX struct"))?;
+    let chain_pem = issuer.map(|ca| ca.chain_pem.clone()).unwrap_or_default();
+    let chain_der = chain_pem
+        .iter()
+        .map(|c| pem::parse(c).map(|p| p.into_contents()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let chain_refs: Vec<&[u8]> = chain_der.iter().map(|c| c.as_slice()).collect();
 
-    let pfx_bytes = pfx.to_der();
+    let pfx = build_pfx(
+        &cert_der,
+        &key_der,
+        &chain_refs,
+        pfx_password,
+        friendly_name,
+        pfx_format,
+    )?;
 
     Ok(GeneratedCert {
         cert_pem,
         key_pem,
-        pfx: pfx_bytes,
+        cert_der,
+        key_der,
+        pfx,
+        chain_pem,
+    })
+}
+
+/// Build a PKCS#12 file holding the key, its certificate and the chain
+pub fn build_pfx(
+    cert_der: &[u8],
+    key_der: &[u8],
+    chain_der: &[&[u8]],
+    password: &str,
+    friendly_name: &str,
+    format: PfxFormat,
+) -> Result<Vec<u8>> {
+    let iterations = match format {
+        PfxFormat::Legacy => {
+            let pfx = p12::PFX::new_with_cas(cert_der, key_der, chain_der, password, friendly_name)
+                .ok_or_else(|| anyhow::anyhow!("Failed to generate PFX struct"))?;
+            return Ok(pfx.to_der());
+        }
+        PfxFormat::Modern { iterations } => u64::from(iterations),
+    };
+
+    let mut chain = vec![p12_keystore::Certificate::from_der(cert_der)?];
+    for der in chain_der {
+        chain.push(p12_keystore::Certificate::from_der(der)?);
+    }
+    // Links the key bag to the leaf certificate bag
+    let local_key_id = Sha256::digest(cert_der).to_vec();
+
+    let mut keystore = p12_keystore::KeyStore::new();
+    keystore.add_entry(
+        friendly_name,
+        p12_keystore::KeyStoreEntry::PrivateKeyChain(p12_keystore::PrivateKeyChain::new(
+            key_der,
+            local_key_id,
+            chain,
+        )),
+    );
+    let pfx = keystore
+        .writer(password)
+        .encryption_algorithm(p12_keystore::EncryptionAlgorithm::PbeWithHmacSha256AndAes256)
+        .encryption_iterations(iterations)
+        .mac_algorithm(p12_keystore::MacAlgorithm::HmacSha256)
+        .mac_iterations(iterations)
+        .write()?;
+    Ok(pfx)
+}
+
+/// Generate a new key and a PKCS#10 certificate signing request for it
+pub fn generate_csr(mut opt: CertOptions) -> Result<GeneratedCsr> {
+    let key_pair = match opt.existing_key.take() {
+        Some(key_pair) => key_pair,
+        None => generate_key_pair(opt.key_type, opt.rsa_bits)?,
+    };
+    let params = build_params(&opt)?;
+
+    let csr = params.serialize_request(&key_pair)?;
+    let csr_pem = if pss::is_pss(opt.key_type) {
+        let der = pss::resign_csr(csr.der(), &key_pair, opt.key_type)?;
+        to_pem("CERTIFICATE REQUEST", &der)
+    } else {
+        csr.pem()?
+    };
+
+    Ok(GeneratedCsr {
+        csr_pem,
+        key_pem: key_pair.serialize_pem(),
+        key_der: key_pair.serialize_der(),
     })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn default_options() -> CertOptions {
-        CertOptions {
-            cn: "localhost".to_string(),
-            sans: vec!["127.0.0.1".to_string()],
-            validity_days: 365,
-            country: None,
-            state: None,
-            city: None,
-            organization: None,
-            org_unit: None,
-            key_type: KeyType::Ecdsa,
-            pfx_password: String::new(),
-        }
-    }
+    use crate::san::san_to_string;
 
     #[test]
     fn test_generate_ecdsa_cert() {
-        let opt = default_options();
+        let opt = CertOptions::default();
         let result = generate_cert(opt);
         assert!(result.is_ok());
         let cert = result.unwrap();
@@ -142,41 +878,630 @@ mod tests {
 
     #[test]
     fn test_generate_ed25519_cert() {
-        let mut opt = default_options();
-        opt.key_type = KeyType::Ed25519;
+        let opt = CertOptions {
+            key_type: KeyType::Ed25519,
+            ..CertOptions::default()
+        };
         let result = generate_cert(opt);
         assert!(result.is_ok());
     }
 
+    fn assert_rsa_cert(bits: usize) {
+        let opt = CertOptions {
+            key_type: KeyType::Rsa,
+            rsa_bits: bits,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+
+        let der = pem::parse(&cert.cert_pem).unwrap();
+        let (_, x509) = x509_parser::parse_x509_certificate(der.contents()).unwrap();
+        let key = crate::inspect::key_info(x509.public_key());
+        assert_eq!(key.algorithm, "RSA");
+        assert_eq!(key.bits, Some(bits));
+    }
+
+    #[test]
+    fn test_generate_rsa_2048_cert() {
+        assert_rsa_cert(2048);
+    }
+
+    #[test]
+    fn test_generate_rsa_3072_cert() {
+        assert_rsa_cert(3072);
+    }
+
+    #[test]
+    fn test_generate_rsa_4096_cert() {
+        assert_rsa_cert(4096);
+    }
+
+    #[test]
+    fn test_generate_rsa_rejects_unsupported_size() {
+        let opt = CertOptions {
+            key_type: KeyType::Rsa,
+            rsa_bits: 1024,
+            ..CertOptions::default()
+        };
+        assert!(generate_cert(opt).is_err());
+    }
+
+    fn signature_oid(cert_pem: &str) -> String {
+        let der = pem::parse(cert_pem).unwrap();
+        let (_, x509) = x509_parser::parse_x509_certificate(der.contents()).unwrap();
+        x509.signature_algorithm.algorithm.to_id_string()
+    }
+
+    #[test]
+    fn test_generate_ecdsa_p384_cert() {
+        let opt = CertOptions {
+            key_type: KeyType::EcdsaP384,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        // ecdsa-with-SHA384
+        assert_eq!(signature_oid(&cert.cert_pem), "1.2.840.10045.4.3.3");
+    }
+
+    #[test]
+    fn test_generate_rsa_sha512_cert() {
+        let opt = CertOptions {
+            key_type: KeyType::RsaSha512,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        // sha512WithRSAEncryption
+        assert_eq!(signature_oid(&cert.cert_pem), "1.2.840.113549.1.1.13");
+    }
+
+    #[test]
+    fn test_generate_rsa_pss_cert_and_renew() {
+        let opt = CertOptions {
+            key_type: KeyType::RsaPssSha384,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        assert_eq!(signature_oid(&cert.cert_pem), "1.2.840.113549.1.1.10");
+
+        let der = pem::parse(&cert.cert_pem).unwrap();
+        let (_, x509) = x509_parser::parse_x509_certificate(der.contents()).unwrap();
+        assert_eq!(signature_key_type(&x509), Some(KeyType::RsaPssSha384));
+
+        // Renewal keeps the signature scheme
+        let key_pair = KeyPair::from_pem(&cert.key_pem).unwrap();
+        let renewed = renew_cert(
+            &cert.cert_pem,
+            &key_pair,
+            None,
+            None,
+            "",
+            PfxFormat::default(),
+        )
+        .unwrap();
+        let der = pem::parse(&renewed.cert_pem).unwrap();
+        let (_, x509) = x509_parser::parse_x509_certificate(der.contents()).unwrap();
+        assert_eq!(signature_key_type(&x509), Some(KeyType::RsaPssSha384));
+    }
+
     #[test]
     fn test_generate_with_full_dn() {
-        let mut opt = default_options();
-        opt.country = Some("JP".to_string());
-        opt.state = Some("Tokyo".to_string());
-        opt.city = Some("Shibuya".to_string());
-        opt.organization = Some("Test Corp".to_string());
-        opt.org_unit = Some("Dev".to_string());
+        let opt = CertOptions {
+            country: Some("JP".to_string()),
+            state: Some("Tokyo".to_string()),
+            city: Some("Shibuya".to_string()),
+            organization: Some("Test Corp".to_string()),
+            org_unit: Some("Dev".to_string()),
+            ..CertOptions::default()
+        };
         let result = generate_cert(opt);
         assert!(result.is_ok());
     }
 
     #[test]
     fn test_generate_with_multiple_sans() {
-        let mut opt = default_options();
-        opt.sans = vec![
-            "127.0.0.1".to_string(),
-            "192.168.1.1".to_string(),
-            "example.local".to_string(),
-        ];
+        let opt = CertOptions {
+            sans: vec![
+                "127.0.0.1".to_string(),
+                "192.168.1.1".to_string(),
+                "example.local".to_string(),
+            ],
+            ..CertOptions::default()
+        };
         let result = generate_cert(opt);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_generate_key_usages() {
+        let opt = CertOptions {
+            key_type: KeyType::Rsa,
+            purpose: Purpose::Client,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        let params = CertificateParams::from_ca_cert_pem(&cert.cert_pem).unwrap();
+        assert_eq!(
+            params.key_usages,
+            vec![
+                KeyUsagePurpose::DigitalSignature,
+                KeyUsagePurpose::KeyEncipherment
+            ]
+        );
+        assert_eq!(
+            params.extended_key_usages,
+            vec![ExtendedKeyUsagePurpose::ClientAuth]
+        );
+
+        // Explicit usages replace the purpose defaults, including custom OIDs
+        let opt = CertOptions {
+            key_usages: Some(vec![KeyUsagePurpose::DigitalSignature]),
+            extended_key_usages: Some(vec![
+                ExtendedKeyUsagePurpose::ServerAuth,
+                ExtendedKeyUsagePurpose::Other(vec![1, 3, 6, 1, 4, 1, 311, 20, 2, 2]),
+            ]),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        let (_, x509) = x509_parser::parse_x509_certificate(&cert.cert_der).unwrap();
+        let eku = x509.extended_key_usage().unwrap().unwrap().value;
+        assert!(eku.server_auth && !eku.client_auth);
+        assert_eq!(eku.other[0].to_id_string(), "1.3.6.1.4.1.311.20.2.2");
+    }
+
+    #[test]
+    fn test_wildcard_apex() {
+        let mut opt = CertOptions {
+            cn: "*.dev.example.local".to_string(),
+            sans: vec![
+                "*.api.example.local".to_string(),
+                "api.example.local".to_string(),
+            ],
+            wildcard_apex: true,
+            ..CertOptions::default()
+        };
+        let params = build_params(&opt).unwrap();
+        let sans: Vec<String> = params.subject_alt_names.iter().map(san_to_string).collect();
+        assert_eq!(
+            sans,
+            vec![
+                "DNS:*.dev.example.local",
+                "DNS:dev.example.local",
+                "DNS:*.api.example.local",
+                "DNS:api.example.local",
+            ]
+        );
+
+        opt.sans = vec!["api.*.example.local".to_string()];
+        assert!(build_params(&opt).is_err());
+    }
+
+    #[test]
+    fn test_server_cn_validated_like_san() {
+        for name in [
+            "api.*.example.local",
+            "f*o.example.local",
+            "bad host.local",
+            "*",
+        ] {
+            let opt = CertOptions {
+                cn: name.to_string(),
+                ..CertOptions::default()
+            };
+            let err = build_params(&opt).unwrap_err().to_string();
+            assert!(
+                err.starts_with(&format!("Invalid CN '{}'", name)),
+                "{}",
+                err
+            );
+
+            let opt = CertOptions {
+                sans: vec![name.to_string()],
+                ..CertOptions::default()
+            };
+            assert!(build_params(&opt).is_err());
+        }
+
+        // Wildcards on a public suffix are warned about (see main) for CN and SAN alike
+        let opt = CertOptions {
+            cn: "*.com".to_string(),
+            ..CertOptions::default()
+        };
+        assert_eq!(
+            san::public_suffix_wildcards(std::slice::from_ref(&opt.cn)),
+            vec![("*.com".to_string(), "com".to_string())]
+        );
+        assert!(build_params(&opt).is_ok());
+
+        let mut opt = CertOptions {
+            cn: "10.0.0.1".to_string(),
+            ..CertOptions::default()
+        };
+        let params = build_params(&opt).unwrap();
+        assert_eq!(san_to_string(&params.subject_alt_names[0]), "IP:10.0.0.1");
+
+        opt.cn = "email:alice@example.com".to_string();
+        assert!(build_params(&opt).is_err());
+    }
+
+    #[test]
+    fn test_generate_client_cert() {
+        let opt = CertOptions {
+            cn: "Alice Smith".to_string(),
+            sans: Vec::new(),
+            email: Some("alice@example.com".to_string()),
+            uri: Some("spiffe://example.org/alice".to_string()),
+            purpose: Purpose::Client,
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+
+        let params = CertificateParams::from_ca_cert_pem(&cert.cert_pem).unwrap();
+        assert_eq!(
+            dn_to_string(&params.distinguished_name),
+            "CN=Alice Smith, emailAddress=alice@example.com"
+        );
+        // No DNS SAN for the CN
+        let sans: Vec<String> = params.subject_alt_names.iter().map(san_to_string).collect();
+        assert_eq!(
+            sans,
+            vec!["email:alice@example.com", "URI:spiffe://example.org/alice"]
+        );
+        assert_eq!(
+            params.extended_key_usages,
+            vec![ExtendedKeyUsagePurpose::ClientAuth]
+        );
+    }
+
+    /// AIA (method, URL) pairs and CRL Distribution Point URLs of a DER certificate
+    fn issuer_urls(der: &[u8]) -> (Vec<(String, String)>, Vec<String>) {
+        use x509_parser::extensions::{DistributionPointName, GeneralName, ParsedExtension};
+
+        let (_, x509) = x509_parser::parse_x509_certificate(der).unwrap();
+        let mut access = Vec::new();
+        let mut crls = Vec::new();
+        for ext in x509.extensions() {
+            match ext.parsed_extension() {
+                ParsedExtension::AuthorityInfoAccess(aia) => {
+                    for desc in &aia.accessdescs {
+                        if let GeneralName::URI(url) = desc.access_location {
+                            access.push((desc.access_method.to_id_string(), url.to_string()));
+                        }
+                    }
+                }
+                ParsedExtension::CRLDistributionPoints(points) => {
+                    for point in points.iter() {
+                        if let Some(DistributionPointName::FullName(names)) =
+                            &point.distribution_point
+                        {
+                            for name in names {
+                                if let GeneralName::URI(url) = name {
+                                    crls.push(url.to_string());
+                                }
+                            }
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        (access, crls)
+    }
+
+    #[test]
+    fn test_issuer_urls_from_options_and_ca_profile() {
+        let opt = CertOptions {
+            ocsp_url: Some("http://127.0.0.1:8080".to_string()),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        assert_eq!(
+            issuer_urls(&cert.cert_der),
+            (
+                vec![(
+                    "1.3.6.1.5.5.7.48.1".to_string(),
+                    "http://127.0.0.1:8080".to_string()
+                )],
+                vec![]
+            )
+        );
+
+        // The CA profile fills in what the options leave out
+        let mut ca = test_root_ca();
+        ca.profile = CaProfile {
+            ocsp_url: Some("http://ocsp.ca.local".to_string()),
+            ca_issuers_url: Some("http://ca.local/ca.crt".to_string()),
+            crl_url: Some("http://ca.local/ca.crl".to_string()),
+            key_type: None,
+        };
+        let opt = CertOptions {
+            ocsp_url: Some("http://127.0.0.1:8080".to_string()),
+            issuer: Some(ca),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        assert_eq!(
+            issuer_urls(&cert.cert_der),
+            (
+                vec![
+                    (
+                        "1.3.6.1.5.5.7.48.1".to_string(),
+                        "http://127.0.0.1:8080".to_string()
+                    ),
+                    (
+                        "1.3.6.1.5.5.7.48.2".to_string(),
+                        "http://ca.local/ca.crt".to_string()
+                    ),
+                ],
+                vec!["http://ca.local/ca.crl".to_string()]
+            )
+        );
+
+        let opt = CertOptions {
+            crl_url: Some("not a url".to_string()),
+            ..CertOptions::default()
+        };
+        assert!(generate_cert(opt).is_err());
+    }
+
+    fn test_root_ca() -> LocalCa {
+        crate::ca::create_root(crate::ca::CaOptions {
+            cn: "Test Root CA".to_string(),
+            validity_days: 3650,
+            organization: None,
+            key_type: KeyType::Ecdsa,
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_generate_signed_by_local_ca() {
+        let opt = CertOptions {
+            issuer: Some(test_root_ca()),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        assert!(cert.cert_pem.contains("BEGIN CERTIFICATE"));
+    }
+
+    #[test]
+    fn test_generate_signed_by_intermediate_includes_chain() {
+        let root = test_root_ca();
+        let intermediate = crate::ca::create_intermediate(
+            &root,
+            crate::ca::CaOptions {
+                cn: "Test Intermediate CA".to_string(),
+                validity_days: 1825,
+                organization: None,
+                key_type: KeyType::Ecdsa,
+            },
+            None,
+        )
+        .unwrap();
+        let opt = CertOptions {
+            issuer: Some(intermediate),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        assert_eq!(cert.chain_pem.len(), 1);
+
+        let pfx = p12_keystore::KeyStore::from_pkcs12(&cert.pfx, "").unwrap();
+        let (_, chain) = pfx.private_key_chain().unwrap();
+        assert_eq!(chain.chain().len(), 2);
+    }
+
+    #[test]
+    fn test_intermediate_path_len_below_parent() {
+        let ca_options = |cn: &str| crate::ca::CaOptions {
+            cn: cn.to_string(),
+            validity_days: 1825,
+            organization: None,
+            key_type: KeyType::Ecdsa,
+        };
+        let path_len = |ca: &LocalCa| match ca.cert.params().is_ca {
+            rcgen::IsCa::Ca(rcgen::BasicConstraints::Constrained(len)) => len,
+            _ => panic!("not a constrained CA"),
+        };
+
+        let root = test_root_ca();
+        let policy =
+            crate::ca::create_intermediate(&root, ca_options("Policy CA"), Some(2)).unwrap();
+        assert_eq!(path_len(&policy), 2);
+
+        // A child may not allow as many CAs below it as its parent
+        assert!(crate::ca::create_intermediate(&policy, ca_options("Wide"), Some(2)).is_err());
+        let issuing =
+            crate::ca::create_intermediate(&policy, ca_options("Issuing CA"), None).unwrap();
+        assert_eq!(path_len(&issuing), 1);
+        let last = crate::ca::create_intermediate(&issuing, ca_options("Last CA"), None).unwrap();
+        assert_eq!(path_len(&last), 0);
+        assert!(crate::ca::create_intermediate(&last, ca_options("Too deep"), None).is_err());
+    }
+
+    #[test]
+    fn test_generate_csr() {
+        let opt = CertOptions {
+            organization: Some("Test Corp".to_string()),
+            ..CertOptions::default()
+        };
+        let csr = generate_csr(opt).unwrap();
+        assert!(csr.csr_pem.contains("BEGIN CERTIFICATE REQUEST"));
+        assert!(csr.key_pem.contains("BEGIN PRIVATE KEY"));
+
+        let parsed = rcgen::CertificateSigningRequestParams::from_pem(&csr.csr_pem).unwrap();
+        assert_eq!(parsed.params.subject_alt_names.len(), 2);
+    }
+
+    #[test]
+    fn test_sign_csr_with_san_override() {
+        let csr = generate_csr(CertOptions::default()).unwrap();
+        let parsed = parse_csr(csr.csr_pem.as_bytes()).unwrap();
+        let issued = sign_csr(
+            parsed,
+            SignOptions {
+                validity_days: 30,
+                sans: Some(vec!["app.local".to_string(), "10.0.0.1".to_string()]),
+                purpose: Purpose::Server,
+                key_usages: None,
+                extended_key_usages: None,
+            },
+            &test_root_ca(),
+        )
+        .unwrap();
+
+        let params = CertificateParams::from_ca_cert_pem(&issued.cert_pem).unwrap();
+        let sans: Vec<String> = params.subject_alt_names.iter().map(san_to_string).collect();
+        assert_eq!(sans, vec!["DNS:app.local", "IP:10.0.0.1"]);
+        assert!(issued.chain_pem.is_empty());
+    }
+
+    #[test]
+    fn test_sign_csr_does_not_grant_requested_usages() {
+        let opt = CertOptions {
+            purpose: Purpose::CodeSigning,
+            extended_key_usages: Some(vec![
+                ExtendedKeyUsagePurpose::CodeSigning,
+                ExtendedKeyUsagePurpose::Any,
+            ]),
+            ..CertOptions::default()
+        };
+        let csr = generate_csr(opt).unwrap();
+        let sign = |extended_key_usages| {
+            let parsed = parse_csr(csr.csr_pem.as_bytes()).unwrap();
+            assert!(parsed
+                .params
+                .extended_key_usages
+                .contains(&ExtendedKeyUsagePurpose::CodeSigning));
+            let issued = sign_csr(
+                parsed,
+                SignOptions {
+                    validity_days: 30,
+                    sans: None,
+                    purpose: Purpose::Server,
+                    key_usages: None,
+                    extended_key_usages,
+                },
+                &test_root_ca(),
+            )
+            .unwrap();
+            CertificateParams::from_ca_cert_pem(&issued.cert_pem).unwrap()
+        };
+
+        // By default the server purpose decides, whatever the requester asked for
+        let params = sign(None);
+        assert_eq!(
+            params.extended_key_usages,
+            vec![ExtendedKeyUsagePurpose::ServerAuth]
+        );
+        assert_eq!(params.key_usages, vec![KeyUsagePurpose::DigitalSignature]);
+
+        // The operator can still grant it explicitly
+        let params = sign(Some(vec![ExtendedKeyUsagePurpose::CodeSigning]));
+        assert_eq!(
+            params.extended_key_usages,
+            vec![ExtendedKeyUsagePurpose::CodeSigning]
+        );
+    }
+
+    #[test]
+    fn test_renew_keeps_key_and_extensions() {
+        let ca = test_root_ca();
+        let opt = CertOptions {
+            sans: vec!["app.local".to_string()],
+            validity_days: 10,
+            issuer: Some(ca),
+            ..CertOptions::default()
+        };
+        let original = generate_cert(opt).unwrap();
+
+        let ca = test_root_ca();
+        let key_pair = KeyPair::from_pem(&original.key_pem).unwrap();
+        let renewed = renew_cert(
+            &original.cert_pem,
+            &key_pair,
+            Some(90),
+            Some(&ca),
+            "",
+            PfxFormat::default(),
+        )
+        .unwrap();
+        assert_eq!(renewed.key_pem, original.key_pem);
+
+        let params = CertificateParams::from_ca_cert_pem(&renewed.cert_pem).unwrap();
+        let sans: Vec<String> = params.subject_alt_names.iter().map(san_to_string).collect();
+        assert_eq!(sans, vec!["DNS:localhost", "DNS:app.local"]);
+        assert_eq!((params.not_after - params.not_before).whole_days(), 90);
+    }
+
+    #[test]
+    fn test_renew_rejects_mismatched_key() {
+        let a = generate_cert(CertOptions::default()).unwrap();
+        let b = generate_cert(CertOptions::default()).unwrap();
+        let key_pair = KeyPair::from_pem(&b.key_pem).unwrap();
+        assert!(renew_cert(&a.cert_pem, &key_pair, None, None, "", PfxFormat::default()).is_err());
+    }
+
+    #[test]
+    fn test_generate_with_existing_key() {
+        let key_pair = KeyPair::generate_for(&rcgen::PKCS_ED25519).unwrap();
+        let public_key = key_pair.public_key_der();
+        let opt = CertOptions {
+            existing_key: Some(key_pair),
+            ..CertOptions::default()
+        };
+        let cert = generate_cert(opt).unwrap();
+        let reloaded = KeyPair::from_pem(&cert.key_pem).unwrap();
+        assert_eq!(reloaded.public_key_der(), public_key);
+    }
+
     #[test]
     fn test_generate_with_pfx_password() {
-        let mut opt = default_options();
-        opt.pfx_password = "testpassword".to_string();
+        let opt = CertOptions {
+            pfx_password: "testpassword".to_string(),
+            ..CertOptions::default()
+        };
         let result = generate_cert(opt);
         assert!(result.is_ok());
+        let pfx = result.unwrap().pfx;
+        assert!(p12_keystore::KeyStore::from_pkcs12(&pfx, "testpassword").is_ok());
+        assert!(p12_keystore::KeyStore::from_pkcs12(&pfx, "wrong").is_err());
+    }
+
+    #[test]
+    fn test_der_outputs_match_pem() {
+        let cert = generate_cert(CertOptions::default()).unwrap();
+        assert_eq!(
+            cert.cert_der,
+            pem::parse(&cert.cert_pem).unwrap().into_contents()
+        );
+        assert_eq!(
+            cert.key_der,
+            pem::parse(&cert.key_pem).unwrap().into_contents()
+        );
+        assert!(x509_parser::parse_x509_certificate(&cert.cert_der).is_ok());
+    }
+
+    #[test]
+    fn test_pfx_formats_round_trip() {
+        // One iteration is the lowest count --pfx-iterations accepts
+        for format in [
+            PfxFormat::default(),
+            PfxFormat::Modern { iterations: 1 },
+            PfxFormat::Legacy,
+        ] {
+            let opt = CertOptions {
+                pfx_password: "secret".to_string(),
+                pfx_format: format,
+                ..CertOptions::default()
+            };
+            let cert = generate_cert(opt).unwrap();
+
+            let pfx = p12_keystore::KeyStore::from_pkcs12(&cert.pfx, "secret").unwrap();
+            let (alias, chain) = pfx.private_key_chain().unwrap();
+            assert_eq!(alias, "localhost");
+            let key = KeyPair::from_pem(&cert.key_pem).unwrap();
+            assert_eq!(chain.key(), key.serialize_der().as_slice());
+            let cert_der = pem::parse(&cert.cert_pem).unwrap().into_contents();
+            assert_eq!(chain.chain()[0].as_der(), cert_der.as_slice());
+        }
     }
 }
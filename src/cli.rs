@@ -1,6 +1,22 @@
-use clap::Parser;
+use crate::crl;
+use crate::usage::PURPOSE_NAMES;
+use clap::builder::PossibleValuesParser;
+use clap::{Args, Parser, Subcommand};
 use std::path::PathBuf;
 
+/// Accepted `--key-type` values
+pub const KEY_TYPE_NAMES: [&str; 9] = [
+    "rsa",
+    "rsa-sha384",
+    "rsa-sha512",
+    "rsa-pss-sha256",
+    "rsa-pss-sha384",
+    "rsa-pss-sha512",
+    "ecdsa",
+    "ecdsa-p384",
+    "ed25519",
+];
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 #[command(help_template = "\
@@ -12,14 +28,29 @@ use std::path::PathBuf;
 {all-args}{after-help}
 ")]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Common Name (e.g. myserver.local)
     #[arg(long, short = 'c')]
     pub cn: Option<String>,
 
-    /// Subject Alternative Names (IP or DNS)
+    /// Subject Alternative Names (IP, DNS, or typed: dns:, ip:, email:, uri:, othername:)
+    ///
+    /// A wildcard directly on a public suffix (*.com, *.co.uk) prints a warning. Only
+    /// top-level domains and a small best-effort subset of the Public Suffix List are
+    /// checked.
     #[arg(long, short = 's', value_delimiter = ',', num_args = 0..)]
     pub sans: Option<Vec<String>>,
 
+    /// Also add the apex domain for each wildcard SAN (*.dev.example adds dev.example)
+    #[arg(long)]
+    pub wildcard_apex: bool,
+
+    /// Add this machine's hostname, localhost and interface addresses as SANs
+    #[arg(long)]
+    pub auto_sans: bool,
+
     /// Validity days
     #[arg(long, short = 'd')]
     pub days: Option<i64>,
@@ -44,6 +75,18 @@ pub struct Cli {
     #[arg(long)]
     pub all: bool,
 
+    /// Client Mode: Client certificate for mutual TLS (clientAuth, no DNS SAN, PFX password prompt)
+    #[arg(long)]
+    pub client: bool,
+
+    /// Email address identifying the client (subject emailAddress and email SAN)
+    #[arg(long)]
+    pub email: Option<String>,
+
+    /// URI identifying the client, e.g. a SPIFFE ID (URI SAN)
+    #[arg(long)]
+    pub uri: Option<String>,
+
     // === New V2.0 arguments ===
     /// Output directory for generated certificates
     #[arg(long, short = 'o')]
@@ -81,27 +124,442 @@ pub struct Cli {
     #[arg(long)]
     pub org_unit: Option<String>,
 
-    /// Key type: rsa, ecdsa, or ed25519
-    #[arg(long, value_parser = ["rsa", "ecdsa", "ed25519"])]
+    /// Key type and signature scheme (e.g. rsa, rsa-pss-sha256, ecdsa-p384, ed25519)
+    #[arg(long, value_parser = KEY_TYPE_NAMES)]
     pub key_type: Option<String>,
 
+    /// RSA key size in bits: 2048, 3072 or 4096 (default: 2048)
+    #[arg(long)]
+    pub rsa_bits: Option<usize>,
+
+    /// Certificate purpose, for the default key usages: server (default), client, code-signing, email
+    #[arg(long, value_parser = PURPOSE_NAMES)]
+    pub purpose: Option<String>,
+
+    /// Key Usage bits, replacing the purpose defaults (e.g. digital-signature,key-encipherment)
+    #[arg(long, value_delimiter = ',')]
+    pub key_usage: Option<Vec<String>>,
+
+    /// Extended Key Usages or custom OIDs, replacing the purpose defaults (e.g. server-auth,client-auth)
+    #[arg(long, visible_alias = "eku", value_delimiter = ',')]
+    pub extended_key_usage: Option<Vec<String>>,
+
+    /// Use an existing private key (PKCS#8, PKCS#1 or SEC1; PEM or DER) instead of generating one
+    #[arg(long)]
+    pub key: Option<PathBuf>,
+
+    /// Passphrase for an encrypted --key (or set SURESIGN_KEY_PASSPHRASE)
+    #[arg(long)]
+    pub key_passphrase: Option<String>,
+
+    /// Output files to write: key, crt, pem, pfx, der, der-key, fullchain, csr, jks, truststore (comma-separated)
+    #[arg(long, value_delimiter = ',')]
+    pub formats: Option<Vec<String>>,
+
+    /// PFX encryption: modern (AES-256, HMAC-SHA256; default) or legacy (RC2/3DES, HMAC-SHA1)
+    #[arg(long, global = true, value_parser = ["modern", "legacy"])]
+    pub pfx_encryption: Option<String>,
+
+    /// PBKDF2 and MAC iteration count for modern PFX files (default: 10000)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub pfx_iterations: Option<u32>,
+
+    /// Password for .jks keystores and truststores (default: the PFX password, or "changeit")
+    #[arg(long)]
+    pub jks_password: Option<String>,
+
+    /// Alias of the private key entry in the .jks keystore (default: the file name)
+    #[arg(long)]
+    pub jks_alias: Option<String>,
+
+    /// Write private keys encrypted (PKCS#8, AES-256-CBC)
+    #[arg(long)]
+    pub encrypt_key: bool,
+
+    /// Key derivation for --encrypt-key: pbkdf2 (default) or scrypt
+    #[arg(long, value_parser = ["pbkdf2", "scrypt"])]
+    pub encrypt_key_kdf: Option<String>,
+
+    /// Read the --encrypt-key passphrase from a file (or set SURESIGN_ENCRYPT_KEY_PASSPHRASE)
+    #[arg(long)]
+    pub encrypt_key_passphrase_file: Option<PathBuf>,
+
     /// Show verbose output
-    #[arg(long, short = 'v')]
+    #[arg(long, short = 'v', global = true)]
     pub verbose: bool,
 
     /// Suppress non-essential output
-    #[arg(long, short = 'q')]
+    #[arg(long, short = 'q', global = true)]
     pub quiet: bool,
 
     /// Path to config file
-    #[arg(long)]
+    #[arg(long, global = true)]
     pub config: Option<PathBuf>,
 
+    /// Sign the certificate with the local CA instead of self-signing it
+    #[arg(long)]
+    pub ca: bool,
+
+    /// Local CA home directory
+    #[arg(long, global = true)]
+    pub ca_dir: Option<PathBuf>,
+
+    /// Inventory of issued certificates (default: inventory.json in the SureSign data directory)
+    #[arg(long, global = true)]
+    pub inventory: Option<PathBuf>,
+
+    /// Sign with the named intermediate CA (implies --ca)
+    #[arg(long)]
+    pub intermediate: Option<String>,
+
+    /// OCSP responder URL to embed as Authority Information Access (default: the CA profile)
+    #[arg(long)]
+    pub ocsp_url: Option<String>,
+
+    /// CA certificate URL to embed as Authority Information Access (default: the CA profile)
+    #[arg(long)]
+    pub ca_issuers_url: Option<String>,
+
+    /// CRL URL to embed as CRL Distribution Points (default: the CA profile)
+    #[arg(long)]
+    pub crl_url: Option<String>,
+
+    /// CSR Mode: write a certificate signing request (.csr) and key instead of a certificate
+    #[arg(long, conflicts_with_all = ["ca", "intermediate"])]
+    pub csr: bool,
+
     // Hidden flag for testing i18n
     #[arg(long, hide = true)]
     pub lang: Option<String>,
 }
 
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Manage the local certificate authority
+    Ca {
+        #[command(subcommand)]
+        action: CaCommand,
+    },
+
+    /// Issue a certificate for a CSR (.csr) from the local CA
+    Sign(SignArgs),
+
+    /// Decode a certificate, CSR, private key or PFX file
+    Inspect(InspectArgs),
+
+    /// Renew an existing certificate, keeping its private key
+    Renew(RenewArgs),
+
+    /// Revoke a certificate issued by the local CA
+    Revoke(RevokeArgs),
+
+    /// Write a certificate revocation list (CRL) for the local CA
+    Crl(CrlArgs),
+
+    /// Answer OCSP requests for certificates issued by the local CA
+    OcspServe(OcspServeArgs),
+
+    /// List the certificates SureSign has issued
+    List(ListArgs),
+
+    /// Search issued certificates by name, subject, SAN, path, serial or fingerprint
+    Search(SearchArgs),
+
+    /// Report days remaining for certificate files; exits 1 (warning) or 2 (critical)
+    Check(CheckArgs),
+}
+
+#[derive(Args, Debug)]
+pub struct CheckArgs {
+    /// Certificate files, or directories to search recursively
+    #[arg(required = true)]
+    pub paths: Vec<PathBuf>,
+
+    /// Warn about certificates expiring within this duration (exit code 1)
+    #[arg(long, default_value = "30d")]
+    pub warning: String,
+
+    /// Fail on certificates expiring within this duration or expired (exit code 2)
+    #[arg(long, default_value = "7d")]
+    pub critical: String,
+
+    /// Print machine-readable JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct ListArgs {
+    #[command(flatten)]
+    pub filter: InventoryFilter,
+}
+
+#[derive(Args, Debug)]
+pub struct SearchArgs {
+    /// Text to look for (case-insensitive)
+    pub query: String,
+
+    #[command(flatten)]
+    pub filter: InventoryFilter,
+}
+
+#[derive(Args, Debug)]
+pub struct InventoryFilter {
+    /// Only certificates expiring within this duration, including expired ones (e.g. 30d, 12h, 2w)
+    #[arg(long)]
+    pub expiring_within: Option<String>,
+
+    /// Only certificates valid for this host name or IP address (wildcard SANs included)
+    #[arg(long)]
+    pub san: Option<String>,
+
+    /// Include certificates whose files were since overwritten (renewed or re-issued)
+    #[arg(long)]
+    pub all: bool,
+
+    /// Print machine-readable JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct OcspServeArgs {
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    pub listen: String,
+
+    /// Sign responses with a delegated responder certificate (ocsp.crt) instead of the CA key
+    #[arg(long)]
+    pub delegated: bool,
+
+    /// Days until the next update (nextUpdate) in each response
+    #[arg(long, default_value_t = 1)]
+    pub next_update: i64,
+
+    /// URL path the responder is reached under (the path of --ocsp-url), for GET requests
+    #[arg(long, default_value = "/")]
+    pub path: String,
+}
+
+#[derive(Args, Debug)]
+pub struct RevokeArgs {
+    /// Serial number (hex), certificate file, or name of a certificate in the output directory
+    pub target: String,
+
+    /// Revocation reason
+    #[arg(long, value_parser = PossibleValuesParser::new(crl::reason_names()))]
+    pub reason: Option<String>,
+
+    /// CA that issued the serial number (default: the root CA; ignored for certificate files)
+    #[arg(long)]
+    pub intermediate: Option<String>,
+
+    /// Directory containing the named certificate
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// Skip confirmation prompts
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct CrlArgs {
+    /// Write the CRL of the named intermediate CA instead of the root
+    #[arg(long)]
+    pub intermediate: Option<String>,
+
+    /// Days until the next update (nextUpdate)
+    #[arg(long, default_value_t = 30)]
+    pub next_update: i64,
+
+    /// Output directory (default: the CA directory)
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// Output file name (without extension, default: ca)
+    #[arg(long, short = 'n')]
+    pub name: Option<String>,
+}
+
+#[derive(Args, Debug)]
+pub struct RenewArgs {
+    /// Name of the certificate to renew (file name without extension)
+    pub name: String,
+
+    /// Directory containing the certificate and key
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// New validity in days (default: same as the current certificate)
+    #[arg(long, short = 'd')]
+    pub days: Option<i64>,
+
+    /// PFX password (leave empty for no password)
+    #[arg(long)]
+    pub pfx_password: Option<String>,
+
+    /// Skip overwrite confirmation
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct InspectArgs {
+    /// File to inspect (PEM, DER or PFX)
+    pub file: PathBuf,
+
+    /// Password for PFX files
+    #[arg(long)]
+    pub password: Option<String>,
+
+    /// Print machine-readable JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+#[derive(Args, Debug)]
+pub struct SignArgs {
+    /// Certificate signing request to sign (PEM or DER)
+    pub csr: PathBuf,
+
+    /// Validity days
+    #[arg(long, short = 'd')]
+    pub days: Option<i64>,
+
+    /// Replace the requested Subject Alternative Names (IP, DNS, or typed: dns:, ip:, email:, uri:, othername:)
+    #[arg(long, short = 's', value_delimiter = ',', num_args = 1..)]
+    pub sans: Option<Vec<String>>,
+
+    /// Sign with the named intermediate CA instead of the root
+    #[arg(long)]
+    pub intermediate: Option<String>,
+
+    /// Certificate purpose, for the issued key usages: server (default), client, code-signing, email
+    #[arg(long, value_parser = PURPOSE_NAMES)]
+    pub purpose: Option<String>,
+
+    /// Key Usage bits to issue, replacing the purpose defaults (the CSR's requested usages are not copied)
+    #[arg(long, value_delimiter = ',')]
+    pub key_usage: Option<Vec<String>>,
+
+    /// Extended Key Usages or custom OIDs to issue, replacing the purpose defaults
+    #[arg(long, visible_alias = "eku", value_delimiter = ',')]
+    pub extended_key_usage: Option<Vec<String>>,
+
+    /// Output directory for the issued certificate
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+
+    /// Output file name (without extension, default: CSR file name)
+    #[arg(long, short = 'n')]
+    pub name: Option<String>,
+
+    /// Also write the certificate as DER (<name>.cer)
+    #[arg(long)]
+    pub der: bool,
+
+    /// Skip confirmation prompts
+    #[arg(long, short = 'y')]
+    pub yes: bool,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CaCommand {
+    /// Create a new root CA in the CA home directory
+    Init {
+        /// Common Name of the root CA
+        #[arg(long, short = 'c')]
+        cn: Option<String>,
+
+        /// Validity days
+        #[arg(long, short = 'd')]
+        days: Option<i64>,
+
+        /// Organization Name
+        #[arg(long)]
+        org: Option<String>,
+
+        /// Key type and signature scheme (e.g. rsa, ecdsa-p384)
+        #[arg(long, value_parser = KEY_TYPE_NAMES)]
+        key_type: Option<String>,
+
+        /// Replace an existing CA in the CA home directory
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        profile: ProfileArgs,
+    },
+
+    /// Create an intermediate CA signed by the root (or another intermediate)
+    Intermediate {
+        /// Name of the intermediate CA
+        name: String,
+
+        /// Common Name of the intermediate CA
+        #[arg(long, short = 'c')]
+        cn: Option<String>,
+
+        /// Validity days
+        #[arg(long, short = 'd')]
+        days: Option<i64>,
+
+        /// Organization Name
+        #[arg(long)]
+        org: Option<String>,
+
+        /// Key type and signature scheme (e.g. rsa, ecdsa-p384)
+        #[arg(long, value_parser = KEY_TYPE_NAMES)]
+        key_type: Option<String>,
+
+        /// Maximum number of CAs allowed below this one (default: one less than the parent's, or 0 under the root CA)
+        #[arg(long)]
+        path_len: Option<u8>,
+
+        /// Name of the intermediate CA to sign with (default: the root CA)
+        #[arg(long)]
+        parent: Option<String>,
+
+        /// Replace an existing intermediate CA with the same name
+        #[arg(long)]
+        force: bool,
+
+        #[command(flatten)]
+        profile: ProfileArgs,
+    },
+
+    /// Show or change the URLs written into certificates issued by a CA
+    Profile {
+        /// Change the profile of the named intermediate CA instead of the root
+        #[arg(long)]
+        intermediate: Option<String>,
+
+        #[command(flatten)]
+        urls: ProfileArgs,
+
+        /// Remove all URLs (before setting any given ones)
+        #[arg(long)]
+        clear: bool,
+    },
+}
+
+/// URLs a CA puts into the certificates it issues
+#[derive(Args, Debug)]
+pub struct ProfileArgs {
+    /// OCSP responder URL (Authority Information Access)
+    #[arg(long)]
+    pub ocsp_url: Option<String>,
+
+    /// URL where the CA certificate is published (Authority Information Access caIssuers)
+    #[arg(long)]
+    pub ca_issuers_url: Option<String>,
+
+    /// URL where the CRL is published (CRL Distribution Points)
+    #[arg(long)]
+    pub crl_url: Option<String>,
+}
+
 pub fn parse() -> Cli {
     Cli::parse()
 }
@@ -9,9 +9,15 @@ pub struct Config {
     /// Common Name
     pub cn: Option<String>,
 
-    /// Subject Alternative Names
+    /// Subject Alternative Names (IP, DNS, or typed: dns:, ip:, email:, uri:, othername:)
     pub sans: Option<Vec<String>>,
 
+    /// Add the apex domain for each wildcard SAN
+    pub wildcard_apex: Option<bool>,
+
+    /// Add the local hostname and interface addresses as SANs
+    pub auto_sans: Option<bool>,
+
     /// Validity in days
     pub days: Option<i64>,
 
@@ -39,8 +45,74 @@ pub struct Config {
     /// Organizational Unit
     pub org_unit: Option<String>,
 
+    /// Client email address (subject emailAddress and email SAN)
+    pub email: Option<String>,
+
+    /// Client URI (URI SAN)
+    pub uri: Option<String>,
+
     /// Key type (rsa, ecdsa, ed25519)
     pub key_type: Option<String>,
+
+    /// RSA key size in bits (2048, 3072, 4096)
+    pub rsa_bits: Option<usize>,
+
+    /// Certificate purpose (server, client, code-signing, email)
+    pub purpose: Option<String>,
+
+    /// Key Usage bits (e.g. digital-signature, key-encipherment)
+    pub key_usage: Option<Vec<String>>,
+
+    /// Extended Key Usages or custom OIDs (e.g. server-auth, client-auth)
+    pub extended_key_usage: Option<Vec<String>>,
+
+    /// Output files to write (key, crt, pem, pfx, der, der-key, fullchain, csr, jks, truststore)
+    pub formats: Option<Vec<String>>,
+
+    /// PFX encryption (modern, legacy)
+    pub pfx_encryption: Option<String>,
+
+    /// PBKDF2 and MAC iteration count for modern PFX files
+    pub pfx_iterations: Option<u32>,
+
+    /// Password for .jks keystores and truststores
+    pub jks_password: Option<String>,
+
+    /// Alias of the private key entry in the .jks keystore
+    pub jks_alias: Option<String>,
+
+    /// Write private keys encrypted
+    pub encrypt_key: Option<bool>,
+
+    /// Key derivation for encrypted keys (pbkdf2, scrypt)
+    pub encrypt_key_kdf: Option<String>,
+
+    /// File holding the passphrase for encrypted keys
+    pub encrypt_key_passphrase_file: Option<String>,
+
+    /// Existing private key to use instead of generating one
+    pub key: Option<String>,
+
+    /// Sign with the local CA instead of self-signing
+    pub ca: Option<bool>,
+
+    /// Local CA home directory
+    pub ca_dir: Option<String>,
+
+    /// Inventory of issued certificates
+    pub inventory: Option<String>,
+
+    /// Intermediate CA to sign with
+    pub intermediate: Option<String>,
+
+    /// OCSP responder URL to embed in certificates
+    pub ocsp_url: Option<String>,
+
+    /// CA certificate URL to embed in certificates (Authority Information Access)
+    pub ca_issuers_url: Option<String>,
+
+    /// CRL URL to embed in certificates (CRL Distribution Points)
+    pub crl_url: Option<String>,
 }
 
 impl Config {
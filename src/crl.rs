@@ -0,0 +1,373 @@
+use crate::ca::{self, LocalCa};
+use crate::cert;
+use crate::cli::{CrlArgs, RevokeArgs};
+use crate::i18n::t;
+use crate::output;
+use crate::pss;
+use anyhow::{bail, Context, Result};
+use inquire::Confirm;
+use rcgen::{
+    CertificateRevocationListParams, KeyIdMethod, RevocationReason, RevokedCertParams, SerialNumber,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+
+/// File name of the revocation database inside a CA directory
+pub const REVOKED_FILE: &str = "revoked.json";
+
+/// CRL reason codes by `--reason` name (RFC 5280 §5.3.1; removeFromCRL only
+/// applies to delta CRLs)
+pub const REASONS: [(&str, RevocationReason); 9] = [
+    ("unspecified", RevocationReason::Unspecified),
+    ("key-compromise", RevocationReason::KeyCompromise),
+    ("ca-compromise", RevocationReason::CaCompromise),
+    ("affiliation-changed", RevocationReason::AffiliationChanged),
+    ("superseded", RevocationReason::Superseded),
+    (
+        "cessation-of-operation",
+        RevocationReason::CessationOfOperation,
+    ),
+    ("certificate-hold", RevocationReason::CertificateHold),
+    ("privilege-withdrawn", RevocationReason::PrivilegeWithdrawn),
+    ("aa-compromise", RevocationReason::AaCompromise),
+];
+
+/// Names accepted by `--reason`
+pub fn reason_names() -> impl Iterator<Item = &'static str> {
+    REASONS.iter().map(|(name, _)| *name)
+}
+
+/// Certificates revoked by one CA, stored as `revoked.json` in its directory
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct RevocationDb {
+    /// Number of the last CRL issued
+    #[serde(default)]
+    pub crl_number: u64,
+    #[serde(default)]
+    pub revoked: Vec<RevokedEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RevokedEntry {
+    /// Serial number as lowercase hex without leading zero bytes
+    pub serial: String,
+    /// Subject of the certificate, when revoked by file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+    /// One of `reason_names()`
+    pub reason: String,
+    /// Revocation time (RFC 3339)
+    pub revoked_at: String,
+}
+
+impl RevocationDb {
+    /// Load the database of a CA directory (empty if nothing was revoked yet)
+    pub fn load(ca_dir: &Path) -> Result<Self> {
+        let path = ca_dir.join(REVOKED_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(&path).context("Failed to read revocation database")?;
+        serde_json::from_str(&content).context("Failed to parse revocation database")
+    }
+
+    pub fn save(&self, ca_dir: &Path) -> Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(ca_dir.join(REVOKED_FILE), content + "\n")
+            .context("Failed to write revocation database")
+    }
+
+    /// Revocation entry for a serial number (any hex form)
+    pub fn find(&self, serial: &str) -> Option<&RevokedEntry> {
+        let serial = normalize_serial(serial)?;
+        self.revoked.iter().find(|e| e.serial == serial)
+    }
+}
+
+/// Parse a `--reason` name
+pub fn parse_reason(name: &str) -> Result<RevocationReason> {
+    match REASONS.iter().find(|(n, _)| *n == name) {
+        Some((_, reason)) => Ok(*reason),
+        None => bail!(
+            "Unknown revocation reason '{}' (expected {})",
+            name,
+            reason_names().collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+/// Normalize a hex serial number (`4A:1B:...`, `0x4a1b...`) to lowercase hex
+/// without separators or leading zero bytes
+pub fn normalize_serial(serial: &str) -> Option<String> {
+    let hex: String = serial
+        .trim()
+        .trim_start_matches("0x")
+        .chars()
+        .filter(|c| *c != ':')
+        .collect::<String>()
+        .to_lowercase();
+    if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let hex = if hex.len() % 2 == 1 {
+        format!("0{}", hex)
+    } else {
+        hex
+    };
+    let mut trimmed = hex.as_str();
+    while trimmed.len() > 2 && trimmed.starts_with("00") {
+        trimmed = &trimmed[2..];
+    }
+    Some(trimmed.to_string())
+}
+
+/// Serial number bytes of a DER certificate, normalized like `normalize_serial`
+pub fn serial_of(raw_serial: &[u8]) -> String {
+    let hex: String = raw_serial.iter().map(|b| format!("{:02x}", b)).collect();
+    normalize_serial(&hex).unwrap_or(hex)
+}
+
+fn serial_bytes(serial: &str) -> Vec<u8> {
+    (0..serial.len())
+        .step_by(2)
+        .filter_map(|i| u8::from_str_radix(&serial[i..i + 2], 16).ok())
+        .collect()
+}
+
+/// Build and sign a CRL listing every certificate in the database (DER)
+pub fn build_crl(issuer: &LocalCa, db: &RevocationDb, next_update_days: i64) -> Result<Vec<u8>> {
+    let revoked_certs = db
+        .revoked
+        .iter()
+        .map(|entry| {
+            Ok(RevokedCertParams {
+                serial_number: SerialNumber::from(serial_bytes(&entry.serial)),
+                revocation_time: OffsetDateTime::parse(&entry.revoked_at, &Rfc3339)
+                    .with_context(|| format!("Invalid revocation time for {}", entry.serial))?,
+                reason_code: Some(parse_reason(&entry.reason)?),
+                invalidity_date: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let now = OffsetDateTime::now_utc();
+    let params = CertificateRevocationListParams {
+        this_update: now,
+        next_update: now + Duration::days(next_update_days),
+        crl_number: SerialNumber::from(db.crl_number.to_be_bytes().to_vec()),
+        issuing_distribution_point: None,
+        revoked_certs,
+        // The authority key identifier must match the CA's subject key identifier
+        key_identifier_method: KeyIdMethod::PreSpecified(issuer.cert.key_identifier()),
+    };
+    let crl = params
+        .signed_by(&issuer.cert, &issuer.key_pair)
+        .context("Failed to sign CRL")?;
+    if pss::is_pss(issuer.key_type) {
+        pss::resign_crl(crl.der(), &issuer.key_pair, issuer.key_type)
+    } else {
+        Ok(crl.der().to_vec())
+    }
+}
+
+/// Handle `suresign revoke <serial|name>`
+pub fn run_revoke(
+    args: RevokeArgs,
+    default_output: Option<PathBuf>,
+    ca_dir: &Path,
+    quiet: bool,
+) -> Result<()> {
+    let reason = args.reason.unwrap_or_else(|| "unspecified".to_string());
+    parse_reason(&reason)?;
+
+    // A certificate file or name (as written by SureSign), otherwise a serial number
+    let output_path = args
+        .output
+        .or(default_output)
+        .unwrap_or_else(|| PathBuf::from("."));
+    let named = output_path.join(format!("{}.crt", args.target));
+    let cert_path = [PathBuf::from(&args.target), named]
+        .into_iter()
+        .find(|p| p.is_file());
+
+    let (issuer_dir, serial, subject) = match cert_path {
+        Some(path) => {
+            let data =
+                fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            let der = match pem::parse(&data) {
+                Ok(block) => block.into_contents(),
+                Err(_) => data,
+            };
+            let (_, x509) = x509_parser::parse_x509_certificate(&der)
+                .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+            let Some(dir) = ca::find_issuer_dir(ca_dir, x509.issuer().as_raw()) else {
+                bail!(
+                    "{} was not issued by a CA in {}",
+                    path.display(),
+                    ca_dir.display()
+                );
+            };
+            (
+                dir,
+                serial_of(x509.raw_serial()),
+                Some(x509.subject().to_string()),
+            )
+        }
+        None => {
+            let Some(serial) = normalize_serial(&args.target) else {
+                bail!(
+                    "'{}' is neither a certificate in {} nor a hex serial number",
+                    args.target,
+                    output_path.display()
+                );
+            };
+            let dir = match &args.intermediate {
+                Some(name) => ca::intermediate_dir(ca_dir, name),
+                None => ca_dir.to_path_buf(),
+            };
+            (dir, serial, None)
+        }
+    };
+
+    let issuer = ca::load(&issuer_dir)?;
+    let mut db = RevocationDb::load(&issuer_dir)?;
+    if let Some(entry) = db.find(&serial) {
+        bail!(
+            "Serial {} was already revoked at {} ({})",
+            serial,
+            entry.revoked_at,
+            entry.reason
+        );
+    }
+
+    println!("{} {}", t("revoke_serial"), serial);
+    if let Some(subject) = &subject {
+        println!("{} {}", t("csr_subject"), subject);
+    }
+    println!("{} {}", t("csr_issuer"), issuer.common_name());
+    println!("{} {}", t("revoke_reason"), reason);
+
+    if !args.yes {
+        let confirmed = Confirm::new(&t("revoke_confirm"))
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false);
+        if !confirmed {
+            println!("{}", t("aborted"));
+            return Ok(());
+        }
+    }
+
+    // CRLs and OCSP responses carry whole seconds
+    let now = OffsetDateTime::now_utc().replace_nanosecond(0)?;
+    db.revoked.push(RevokedEntry {
+        serial,
+        subject,
+        reason,
+        revoked_at: now.format(&Rfc3339)?,
+    });
+    db.save(&issuer_dir)?;
+
+    if !quiet {
+        println!("{}", t("revoked"));
+        println!("{}", t("revoke_crl_hint"));
+    }
+    Ok(())
+}
+
+/// Handle `suresign crl`
+pub fn run_crl(args: CrlArgs, ca_dir: &Path, quiet: bool) -> Result<()> {
+    if args.next_update < 1 {
+        bail!("--next-update must be at least 1 day");
+    }
+    let issuer_dir = match &args.intermediate {
+        Some(name) => ca::intermediate_dir(ca_dir, name),
+        None => ca_dir.to_path_buf(),
+    };
+    let issuer = ca::load(&issuer_dir)?;
+
+    let mut db = RevocationDb::load(&issuer_dir)?;
+    db.crl_number += 1;
+    let crl = build_crl(&issuer, &db, args.next_update)?;
+
+    let output_path = args.output.unwrap_or_else(|| issuer_dir.clone());
+    if !output_path.exists() {
+        fs::create_dir_all(&output_path).context("Failed to create output directory")?;
+    }
+    let name = args.name.unwrap_or_else(|| "ca".to_string());
+    let der_path = output_path.join(format!("{}.crl", name));
+    let pem_path = output_path.join(format!("{}-crl.pem", name));
+
+    // CRLs are re-issued regularly, so they are replaced without asking
+    fs::write(&der_path, &crl).context("Failed to write CRL")?;
+    fs::write(&pem_path, cert::to_pem("X509 CRL", &crl)).context("Failed to write CRL")?;
+    db.save(&issuer_dir)?;
+
+    if !quiet {
+        output::print_saved(&output_path, &[&der_path, &pem_path])?;
+        println!(
+            "{}",
+            t("crl_summary")
+                .replace("{number}", &db.crl_number.to_string())
+                .replace("{count}", &db.revoked.len().to_string())
+                .replace("{days}", &args.next_update.to_string())
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ca::CaOptions;
+    use crate::cert::KeyType;
+    use x509_parser::prelude::FromDer;
+    use x509_parser::revocation_list::CertificateRevocationList as ParsedCrl;
+
+    #[test]
+    fn test_normalize_serial() {
+        assert_eq!(normalize_serial("4A:1B:00").as_deref(), Some("4a1b00"));
+        assert_eq!(normalize_serial("0x00ff").as_deref(), Some("ff"));
+        assert_eq!(normalize_serial("abc").as_deref(), Some("0abc"));
+        assert_eq!(normalize_serial("00").as_deref(), Some("00"));
+        assert_eq!(normalize_serial("server"), None);
+        assert_eq!(serial_of(&[0x00, 0x80, 0x01]), "8001");
+    }
+
+    #[test]
+    fn test_crl_lists_revoked_serials() {
+        let ca = ca::create_root(CaOptions {
+            cn: "Test CA".to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type: KeyType::Ecdsa,
+        })
+        .unwrap();
+        let db = RevocationDb {
+            crl_number: 3,
+            revoked: vec![RevokedEntry {
+                serial: "4a1b".to_string(),
+                subject: None,
+                reason: "key-compromise".to_string(),
+                revoked_at: "2026-01-02T03:04:05Z".to_string(),
+            }],
+        };
+        let crl = build_crl(&ca, &db, 7).unwrap();
+
+        let (_, parsed) = ParsedCrl::from_der(&crl).unwrap();
+        let ca_der = pem::parse(&ca.cert_pem).unwrap().into_contents();
+        let (_, ca_x509) = x509_parser::parse_x509_certificate(&ca_der).unwrap();
+        assert_eq!(parsed.issuer().as_raw(), ca_x509.subject().as_raw());
+        assert_eq!(parsed.crl_number().map(|n| n.to_string()), Some("3".into()));
+        let revoked: Vec<_> = parsed.iter_revoked_certificates().collect();
+        assert_eq!(revoked.len(), 1);
+        assert_eq!(revoked[0].raw_serial(), &[0x4a, 0x1b]);
+        assert_eq!(
+            revoked[0].reason_code().map(|(_, r)| r.0),
+            Some(RevocationReason::KeyCompromise as u8)
+        );
+    }
+}
@@ -30,7 +30,7 @@ fn embedded_en() -> HashMap<String, String> {
     );
     m.insert(
         "enter_san".to_string(),
-        "Subject Alternative Names (SANs) - comma separated (e.g., 192.168.1.1, dns.local):"
+        "Subject Alternative Names (SANs) - comma separated (e.g., 192.168.1.1, dns.local, email:me@example.com, uri:spiffe://example.org/app):"
             .to_string(),
     );
     m.insert("enter_days".to_string(), "Validity days:".to_string());
@@ -80,6 +80,208 @@ fn embedded_en() -> HashMap<String, String> {
         "Overwrite? (y/N):".to_string(),
     );
     m.insert("aborted".to_string(), "Aborted.".to_string());
+    m.insert("ca_created".to_string(), "Local CA created:".to_string());
+    m.insert(
+        "ca_install_hint".to_string(),
+        "Install ca.crt into your trust store once to trust every certificate it issues."
+            .to_string(),
+    );
+    m.insert(
+        "generating_csr".to_string(),
+        "Generating certificate signing request...".to_string(),
+    );
+    m.insert("csr_subject".to_string(), "Subject:".to_string());
+    m.insert("csr_sans".to_string(), "Requested SANs:".to_string());
+    m.insert(
+        "csr_sans_override".to_string(),
+        "SANs to issue:".to_string(),
+    );
+    m.insert("csr_issuer".to_string(), "Issuer:".to_string());
+    m.insert(
+        "sign_confirm".to_string(),
+        "Issue a certificate for this request?".to_string(),
+    );
+    m.insert("inspect_certificate".to_string(), "Certificate".to_string());
+    m.insert(
+        "inspect_csr".to_string(),
+        "Certificate Signing Request".to_string(),
+    );
+    m.insert("inspect_private_key".to_string(), "Private Key".to_string());
+    m.insert("inspect_subject".to_string(), "Subject:".to_string());
+    m.insert("inspect_issuer".to_string(), "Issuer:".to_string());
+    m.insert("inspect_serial".to_string(), "Serial:".to_string());
+    m.insert("inspect_not_before".to_string(), "Valid from:".to_string());
+    m.insert("inspect_not_after".to_string(), "Valid until:".to_string());
+    m.insert(
+        "inspect_days_remaining".to_string(),
+        "{days} days remaining".to_string(),
+    );
+    m.insert("inspect_expired".to_string(), "expired".to_string());
+    m.insert("inspect_sans".to_string(), "SANs:".to_string());
+    m.insert("inspect_key".to_string(), "Public key:".to_string());
+    m.insert("inspect_is_ca".to_string(), "CA:".to_string());
+    m.insert("inspect_extensions".to_string(), "Extensions:".to_string());
+    m.insert(
+        "inspect_fingerprint".to_string(),
+        "SHA-256 fingerprint:".to_string(),
+    );
+    m.insert("inspect_format".to_string(), "Format:".to_string());
+    m.insert(
+        "renewing".to_string(),
+        "Renewing certificate...".to_string(),
+    );
+    m.insert(
+        "enter_key_passphrase".to_string(),
+        "Private key passphrase:".to_string(),
+    );
+    m.insert(
+        "select_rsa_bits".to_string(),
+        "Select RSA key size (bits):".to_string(),
+    );
+    m.insert(
+        "enter_encrypt_passphrase".to_string(),
+        "Passphrase to encrypt the private key:".to_string(),
+    );
+    m.insert(
+        "confirm_passphrase".to_string(),
+        "Confirm passphrase:".to_string(),
+    );
+    m.insert(
+        "passphrase_mismatch".to_string(),
+        "The passphrases don't match.".to_string(),
+    );
+    m.insert("select_key_usage".to_string(), "Key Usage:".to_string());
+    m.insert(
+        "select_extended_key_usage".to_string(),
+        "Extended Key Usage:".to_string(),
+    );
+    m.insert(
+        "enter_custom_eku".to_string(),
+        "Custom Extended Key Usage OIDs (comma-separated, optional):".to_string(),
+    );
+    m.insert(
+        "client_mode".to_string(),
+        "Client Mode: creating a client certificate for mutual TLS.".to_string(),
+    );
+    m.insert(
+        "enter_client_cn".to_string(),
+        "Client name (Common Name):".to_string(),
+    );
+    m.insert(
+        "enter_client_email".to_string(),
+        "Client email address (optional):".to_string(),
+    );
+    m.insert(
+        "enter_client_uri".to_string(),
+        "Client URI, e.g. spiffe://example.org/service (optional):".to_string(),
+    );
+    m.insert(
+        "idn_names".to_string(),
+        "Internationalized domain names (stored as punycode):".to_string(),
+    );
+    m.insert("confirm_wildcard_apex".to_string(), "Also include the apex domain for each wildcard (e.g. dev.example.local for *.dev.example.local)?".to_string());
+    m.insert("wildcard_public_suffix".to_string(), "Warning: {name} is a wildcard directly on the public suffix {suffix}; browsers reject it and it would match unrelated sites. (SureSign checks top-level domains and a small best-effort subset of the Public Suffix List, so other suffixes are not caught.)".to_string());
+    m.insert(
+        "select_auto_sans".to_string(),
+        "Select the local names to include as SANs:".to_string(),
+    );
+    m.insert("revoke_serial".to_string(), "Serial:".to_string());
+    m.insert("revoke_reason".to_string(), "Reason:".to_string());
+    m.insert(
+        "revoke_confirm".to_string(),
+        "Revoke this certificate?".to_string(),
+    );
+    m.insert("revoked".to_string(), "Certificate revoked.".to_string());
+    m.insert(
+        "revoke_crl_hint".to_string(),
+        "Run `suresign crl` to publish an updated CRL.".to_string(),
+    );
+    m.insert(
+        "crl_summary".to_string(),
+        "CRL #{number}: {count} revoked certificate(s), next update in {days} day(s)".to_string(),
+    );
+    m.insert(
+        "ocsp_listening".to_string(),
+        "OCSP responder listening on http://{addr} (Ctrl+C to stop)".to_string(),
+    );
+    m.insert(
+        "ocsp_ca_signed".to_string(),
+        "signed by the CA key".to_string(),
+    );
+    m.insert("profile_none".to_string(), "(none)".to_string());
+    m.insert(
+        "inventory_failed".to_string(),
+        "Warning: could not record the certificate in the inventory: {error}".to_string(),
+    );
+    m.insert(
+        "inventory_empty".to_string(),
+        "No certificates found.".to_string(),
+    );
+    m.insert("check_ok".to_string(), "OK".to_string());
+    m.insert("check_warning".to_string(), "WARNING".to_string());
+    m.insert("check_critical".to_string(), "CRITICAL".to_string());
+    m.insert("check_expired".to_string(), "EXPIRED".to_string());
+    m.insert(
+        "check_summary".to_string(),
+        "{count} certificate(s) checked: {warning} warning, {critical} critical".to_string(),
+    );
+    m.insert("inspect_yes".to_string(), "yes".to_string());
+    m.insert("inspect_no".to_string(), "no".to_string());
+    m.insert("inspect_critical".to_string(), "critical".to_string());
+    m.insert(
+        "inspect_ext_authority_key_id".to_string(),
+        "Authority Key Identifier".to_string(),
+    );
+    m.insert(
+        "inspect_ext_subject_key_id".to_string(),
+        "Subject Key Identifier".to_string(),
+    );
+    m.insert("inspect_ext_key_usage".to_string(), "Key Usage".to_string());
+    m.insert(
+        "inspect_ext_subject_alt_name".to_string(),
+        "Subject Alternative Name".to_string(),
+    );
+    m.insert(
+        "inspect_ext_basic_constraints".to_string(),
+        "Basic Constraints".to_string(),
+    );
+    m.insert(
+        "inspect_ext_extended_key_usage".to_string(),
+        "Extended Key Usage".to_string(),
+    );
+    m.insert(
+        "inspect_ext_crl_distribution_points".to_string(),
+        "CRL Distribution Points".to_string(),
+    );
+    m.insert(
+        "inspect_ext_authority_info_access".to_string(),
+        "Authority Information Access".to_string(),
+    );
+    m.insert(
+        "inspect_ext_name_constraints".to_string(),
+        "Name Constraints".to_string(),
+    );
+    m.insert(
+        "inspect_ext_unknown".to_string(),
+        "Unknown extension".to_string(),
+    );
+    m.insert("check_unknown".to_string(), "UNKNOWN".to_string());
+    m.insert(
+        "check_unreadable".to_string(),
+        "{count} path(s) could not be read".to_string(),
+    );
+    m.insert(
+        "csr_usages".to_string(),
+        "Requested key usage (not copied):".to_string(),
+    );
+    m.insert(
+        "csr_usages_issued".to_string(),
+        "Key usage to issue:".to_string(),
+    );
+    m.insert(
+        "ocsp_intermediate_skipped".to_string(),
+        "Warning: skipping intermediate CA {name}: {error}".to_string(),
+    );
     m
 }
 
@@ -102,7 +304,7 @@ fn embedded_ja() -> HashMap<String, String> {
     );
     m.insert(
         "enter_san".to_string(),
-        "サブジェクト代替名 (SANs) - カンマ区切り (例: 192.168.1.1, dns.local):".to_string(),
+        "サブジェクト代替名 (SANs) - カンマ区切り (例: 192.168.1.1, dns.local, email:me@example.com, uri:spiffe://example.org/app):".to_string(),
     );
     m.insert("enter_days".to_string(), "有効期限 (日数):".to_string());
     m.insert("generating".to_string(), "証明書を生成中...".to_string());
@@ -133,6 +335,217 @@ fn embedded_ja() -> HashMap<String, String> {
         "上書きしますか? (y/N):".to_string(),
     );
     m.insert("aborted".to_string(), "中断しました。".to_string());
+    m.insert(
+        "ca_created".to_string(),
+        "ローカルCAを作成しました:".to_string(),
+    );
+    m.insert(
+        "ca_install_hint".to_string(),
+        "ca.crt を一度信頼ストアに登録すると、このCAが発行した証明書がすべて信頼されます。"
+            .to_string(),
+    );
+    m.insert(
+        "generating_csr".to_string(),
+        "証明書署名要求 (CSR) を生成中...".to_string(),
+    );
+    m.insert("csr_subject".to_string(), "サブジェクト:".to_string());
+    m.insert("csr_sans".to_string(), "要求されたSAN:".to_string());
+    m.insert("csr_sans_override".to_string(), "発行するSAN:".to_string());
+    m.insert("csr_issuer".to_string(), "発行者:".to_string());
+    m.insert(
+        "sign_confirm".to_string(),
+        "この要求に対して証明書を発行しますか?".to_string(),
+    );
+    m.insert("inspect_certificate".to_string(), "証明書".to_string());
+    m.insert(
+        "inspect_csr".to_string(),
+        "証明書署名要求 (CSR)".to_string(),
+    );
+    m.insert("inspect_private_key".to_string(), "秘密鍵".to_string());
+    m.insert("inspect_subject".to_string(), "サブジェクト:".to_string());
+    m.insert("inspect_issuer".to_string(), "発行者:".to_string());
+    m.insert("inspect_serial".to_string(), "シリアル番号:".to_string());
+    m.insert(
+        "inspect_not_before".to_string(),
+        "有効期間 (開始):".to_string(),
+    );
+    m.insert(
+        "inspect_not_after".to_string(),
+        "有効期間 (終了):".to_string(),
+    );
+    m.insert(
+        "inspect_days_remaining".to_string(),
+        "残り {days} 日".to_string(),
+    );
+    m.insert("inspect_expired".to_string(), "期限切れ".to_string());
+    m.insert(
+        "inspect_sans".to_string(),
+        "サブジェクト代替名:".to_string(),
+    );
+    m.insert("inspect_key".to_string(), "公開鍵:".to_string());
+    m.insert("inspect_is_ca".to_string(), "CA:".to_string());
+    m.insert("inspect_extensions".to_string(), "拡張:".to_string());
+    m.insert(
+        "inspect_fingerprint".to_string(),
+        "SHA-256 フィンガープリント:".to_string(),
+    );
+    m.insert("inspect_format".to_string(), "形式:".to_string());
+    m.insert("renewing".to_string(), "証明書を更新中...".to_string());
+    m.insert(
+        "enter_key_passphrase".to_string(),
+        "秘密鍵のパスフレーズ:".to_string(),
+    );
+    m.insert(
+        "select_rsa_bits".to_string(),
+        "RSA鍵のサイズ (ビット) を選択:".to_string(),
+    );
+    m.insert(
+        "enter_encrypt_passphrase".to_string(),
+        "秘密鍵を暗号化するパスフレーズ:".to_string(),
+    );
+    m.insert(
+        "confirm_passphrase".to_string(),
+        "パスフレーズ (確認):".to_string(),
+    );
+    m.insert(
+        "passphrase_mismatch".to_string(),
+        "パスフレーズが一致しません。".to_string(),
+    );
+    m.insert(
+        "select_key_usage".to_string(),
+        "キー使用法 (Key Usage):".to_string(),
+    );
+    m.insert(
+        "select_extended_key_usage".to_string(),
+        "拡張キー使用法 (Extended Key Usage):".to_string(),
+    );
+    m.insert(
+        "enter_custom_eku".to_string(),
+        "カスタム拡張キー使用法の OID (カンマ区切り、任意):".to_string(),
+    );
+    m.insert(
+        "client_mode".to_string(),
+        "クライアントモード: 相互 TLS 用のクライアント証明書を作成します。".to_string(),
+    );
+    m.insert(
+        "enter_client_cn".to_string(),
+        "クライアント名 (Common Name):".to_string(),
+    );
+    m.insert(
+        "enter_client_email".to_string(),
+        "クライアントのメールアドレス (任意):".to_string(),
+    );
+    m.insert(
+        "enter_client_uri".to_string(),
+        "クライアント URI (例: spiffe://example.org/service、任意):".to_string(),
+    );
+    m.insert(
+        "idn_names".to_string(),
+        "国際化ドメイン名 (Punycode で格納):".to_string(),
+    );
+    m.insert("confirm_wildcard_apex".to_string(), "ワイルドカードごとに apex ドメインも含めますか (例: *.dev.example.local に対して dev.example.local)?".to_string());
+    m.insert("wildcard_public_suffix".to_string(), "警告: {name} はパブリックサフィックス {suffix} 直下のワイルドカードです。ブラウザに拒否され、無関係のサイトにも一致します。(SureSign が確認するのはトップレベルドメインと Public Suffix List の一部のみで、それ以外のサフィックスは検出されません。)".to_string());
+    m.insert(
+        "select_auto_sans".to_string(),
+        "SAN に含めるローカル名を選択してください:".to_string(),
+    );
+    m.insert("revoke_serial".to_string(), "シリアル番号:".to_string());
+    m.insert("revoke_reason".to_string(), "理由:".to_string());
+    m.insert(
+        "revoke_confirm".to_string(),
+        "この証明書を失効させますか?".to_string(),
+    );
+    m.insert(
+        "revoked".to_string(),
+        "証明書を失効させました。".to_string(),
+    );
+    m.insert(
+        "revoke_crl_hint".to_string(),
+        "`suresign crl` を実行して更新された CRL を発行してください。".to_string(),
+    );
+    m.insert(
+        "crl_summary".to_string(),
+        "CRL #{number}: 失効済み証明書 {count} 件、次回更新は {days} 日後".to_string(),
+    );
+    m.insert(
+        "ocsp_listening".to_string(),
+        "OCSP レスポンダーを http://{addr} で待ち受けています (Ctrl+C で終了)".to_string(),
+    );
+    m.insert("ocsp_ca_signed".to_string(), "CA の鍵で署名".to_string());
+    m.insert("profile_none".to_string(), "(なし)".to_string());
+    m.insert(
+        "inventory_failed".to_string(),
+        "警告: 証明書をインベントリに記録できませんでした: {error}".to_string(),
+    );
+    m.insert(
+        "inventory_empty".to_string(),
+        "証明書が見つかりませんでした。".to_string(),
+    );
+    m.insert("check_ok".to_string(), "OK".to_string());
+    m.insert("check_warning".to_string(), "警告".to_string());
+    m.insert("check_critical".to_string(), "緊急".to_string());
+    m.insert("check_expired".to_string(), "期限切れ".to_string());
+    m.insert(
+        "check_summary".to_string(),
+        "{count} 件の証明書を確認: 警告 {warning} 件、緊急 {critical} 件".to_string(),
+    );
+    m.insert("inspect_yes".to_string(), "はい".to_string());
+    m.insert("inspect_no".to_string(), "いいえ".to_string());
+    m.insert("inspect_critical".to_string(), "重要".to_string());
+    m.insert(
+        "inspect_ext_authority_key_id".to_string(),
+        "機関鍵識別子".to_string(),
+    );
+    m.insert(
+        "inspect_ext_subject_key_id".to_string(),
+        "サブジェクト鍵識別子".to_string(),
+    );
+    m.insert(
+        "inspect_ext_key_usage".to_string(),
+        "キー使用法".to_string(),
+    );
+    m.insert(
+        "inspect_ext_subject_alt_name".to_string(),
+        "サブジェクト代替名".to_string(),
+    );
+    m.insert(
+        "inspect_ext_basic_constraints".to_string(),
+        "基本制約".to_string(),
+    );
+    m.insert(
+        "inspect_ext_extended_key_usage".to_string(),
+        "拡張キー使用法".to_string(),
+    );
+    m.insert(
+        "inspect_ext_crl_distribution_points".to_string(),
+        "CRL配布ポイント".to_string(),
+    );
+    m.insert(
+        "inspect_ext_authority_info_access".to_string(),
+        "機関情報アクセス".to_string(),
+    );
+    m.insert(
+        "inspect_ext_name_constraints".to_string(),
+        "名前制約".to_string(),
+    );
+    m.insert("inspect_ext_unknown".to_string(), "不明な拡張".to_string());
+    m.insert("check_unknown".to_string(), "不明".to_string());
+    m.insert(
+        "check_unreadable".to_string(),
+        "{count} 件のパスを読み取れませんでした".to_string(),
+    );
+    m.insert(
+        "csr_usages".to_string(),
+        "要求されたキー使用法 (コピーされません):".to_string(),
+    );
+    m.insert(
+        "csr_usages_issued".to_string(),
+        "発行するキー使用法:".to_string(),
+    );
+    m.insert(
+        "ocsp_intermediate_skipped".to_string(),
+        "警告: 中間 CA {name} をスキップします: {error}".to_string(),
+    );
     m
 }
 
@@ -0,0 +1,663 @@
+use crate::cli::InspectArgs;
+use crate::i18n::t;
+use crate::key::{self, KeyFormat};
+use anyhow::{bail, Context, Result};
+use console::Style;
+use der::asn1::ObjectIdentifier;
+use der::{Any, Decode, Sequence};
+use rcgen::KeyPair;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+use x509_parser::certification_request::X509CertificationRequest;
+use x509_parser::cri_attributes::ParsedCriAttribute;
+use x509_parser::extensions::{GeneralName, ParsedExtension, X509Extension};
+use x509_parser::objects::{oid2sn, oid_registry};
+use x509_parser::prelude::{FromDer, X509Certificate};
+use x509_parser::public_key::PublicKey;
+use x509_parser::x509::SubjectPublicKeyInfo;
+
+/// One decoded object found in the inspected file
+#[derive(Serialize, Debug)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Item {
+    Certificate(CertInfo),
+    CertificateRequest(CsrInfo),
+    PrivateKey(PrivateKeyInfo),
+}
+
+#[derive(Serialize, Debug)]
+pub struct CertInfo {
+    pub subject: String,
+    pub issuer: String,
+    pub serial: String,
+    pub not_before: String,
+    pub not_after: String,
+    pub days_remaining: i64,
+    pub subject_alt_names: Vec<String>,
+    pub public_key: KeyInfo,
+    pub is_ca: bool,
+    pub extensions: Vec<ExtensionInfo>,
+    pub sha256_fingerprint: String,
+}
+
+#[derive(Serialize, Debug)]
+pub struct CsrInfo {
+    pub subject: String,
+    pub subject_alt_names: Vec<String>,
+    pub public_key: KeyInfo,
+    pub extensions: Vec<ExtensionInfo>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct PrivateKeyInfo {
+    /// Container format (PKCS#8, PKCS#1, SEC1, ...)
+    pub format: String,
+    /// Key details, when the key could be decoded
+    pub key: Option<KeyInfo>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct KeyInfo {
+    pub algorithm: String,
+    pub bits: Option<usize>,
+}
+
+impl KeyInfo {
+    /// Algorithm with the key size, e.g. `RSA (2048 bits)`
+    pub fn description(&self) -> String {
+        match self.bits {
+            Some(bits) => format!("{} ({} bits)", self.algorithm, bits),
+            None => self.algorithm.clone(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct ExtensionInfo {
+    pub name: String,
+    pub oid: String,
+    pub critical: bool,
+    pub value: String,
+}
+
+/// Handle `suresign inspect <file>`
+pub fn run(args: InspectArgs) -> Result<()> {
+    let data = fs::read(&args.file).context("Failed to read file")?;
+    let items = inspect_bytes(&data, &args.file, args.password.as_deref().unwrap_or(""))?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&items)?);
+    } else {
+        for (i, item) in items.iter().enumerate() {
+            if i > 0 {
+                println!();
+            }
+            print_item(item);
+        }
+    }
+
+    Ok(())
+}
+
+/// Decode every certificate, CSR and key in a PEM, DER or PFX file
+pub fn inspect_bytes(data: &[u8], path: &Path, password: &str) -> Result<Vec<Item>> {
+    let is_pfx_name = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| matches!(e.to_lowercase().as_str(), "pfx" | "p12"))
+        .unwrap_or(false);
+
+    if let Ok(text) = std::str::from_utf8(data) {
+        if text.contains("-----BEGIN") {
+            return inspect_pem(text);
+        }
+    }
+
+    if is_pfx_name {
+        return inspect_pfx(data, password);
+    }
+
+    if let Ok((_, cert)) = X509Certificate::from_der(data) {
+        return Ok(vec![Item::Certificate(cert_info(&cert, data))]);
+    }
+    if let Ok((_, csr)) = X509CertificationRequest::from_der(data) {
+        return Ok(vec![Item::CertificateRequest(csr_info(&csr))]);
+    }
+    if let Ok(item) = pkcs8_key_info(data) {
+        return Ok(vec![item]);
+    }
+    if looks_like_pfx(data) {
+        return inspect_pfx(data, password);
+    }
+
+    bail!("Unrecognized file format (expected PEM, DER or PFX)")
+}
+
+fn inspect_pem(text: &str) -> Result<Vec<Item>> {
+    let blocks = pem::parse_many(text).context("Failed to parse PEM")?;
+    let mut items = Vec::new();
+
+    for block in &blocks {
+        let der = block.contents();
+        let item = match block.tag() {
+            "CERTIFICATE" | "TRUSTED CERTIFICATE" => {
+                let (_, cert) =
+                    X509Certificate::from_der(der).context("Failed to parse certificate")?;
+                Item::Certificate(cert_info(&cert, der))
+            }
+            "CERTIFICATE REQUEST" | "NEW CERTIFICATE REQUEST" => {
+                let (_, csr) =
+                    X509CertificationRequest::from_der(der).context("Failed to parse CSR")?;
+                Item::CertificateRequest(csr_info(&csr))
+            }
+            "PRIVATE KEY" => pkcs8_key_info(der)?,
+            "ENCRYPTED PRIVATE KEY" => Item::PrivateKey(PrivateKeyInfo {
+                format: "PKCS#8 (encrypted)".to_string(),
+                key: None,
+            }),
+            "RSA PRIVATE KEY" => converted_key_info(KeyFormat::Pkcs1, "PKCS#1", der)?,
+            "EC PRIVATE KEY" => converted_key_info(KeyFormat::Sec1, "SEC1", der)?,
+            // Skip blocks we do not understand (e.g. EC PARAMETERS)
+            _ => continue,
+        };
+        items.push(item);
+    }
+
+    if items.is_empty() {
+        bail!("No certificates, CSRs or keys found in PEM file");
+    }
+    Ok(items)
+}
+
+/// Outer PFX structure from RFC 7292; the contents are left to p12_keystore
+#[derive(Sequence)]
+struct PfxHeader {
+    version: u8,
+    auth_safe: ContentInfo,
+    mac_data: Option<Any>,
+}
+
+#[derive(Sequence)]
+struct ContentInfo {
+    content_type: ObjectIdentifier,
+    #[asn1(context_specific = "0", optional = "true")]
+    content: Option<Any>,
+}
+
+const PKCS7_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.1");
+const PKCS7_SIGNED_DATA: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.7.2");
+
+fn looks_like_pfx(data: &[u8]) -> bool {
+    PfxHeader::from_der(data).is_ok_and(|pfx| {
+        pfx.version == 3 && matches!(pfx.auth_safe.content_type, PKCS7_DATA | PKCS7_SIGNED_DATA)
+    })
+}
+
+fn inspect_pfx(data: &[u8], password: &str) -> Result<Vec<Item>> {
+    // Handles both modern (PBES2/AES) and legacy (RC2/3DES) encryption
+    let keystore = match p12_keystore::KeyStore::from_pkcs12(data, password) {
+        Ok(keystore) => keystore,
+        Err(p12_keystore::error::Error::MacError(_)) => {
+            bail!("Wrong PFX password (use --password)")
+        }
+        Err(e) => bail!("Failed to read PFX: {}", e),
+    };
+
+    let mut certs = Vec::new();
+    let mut keys = Vec::new();
+    for (_, entry) in keystore.entries() {
+        match entry {
+            p12_keystore::KeyStoreEntry::PrivateKeyChain(chain) => {
+                certs.extend(chain.chain().iter().map(|c| c.as_der().to_vec()));
+                keys.push(chain.key().to_vec());
+            }
+            p12_keystore::KeyStoreEntry::Certificate(cert) => certs.push(cert.as_der().to_vec()),
+        }
+    }
+
+    let mut items = Vec::new();
+    for der in &certs {
+        let (_, cert) = X509Certificate::from_der(der).context("Failed to parse certificate")?;
+        items.push(Item::Certificate(cert_info(&cert, der)));
+    }
+    for der in &keys {
+        items.push(pkcs8_key_info(der)?);
+    }
+
+    Ok(items)
+}
+
+/// Summarize a parsed certificate
+pub fn cert_info(cert: &X509Certificate, der: &[u8]) -> CertInfo {
+    let validity = cert.validity();
+    let not_after = validity.not_after.to_datetime();
+
+    CertInfo {
+        subject: cert.subject().to_string(),
+        issuer: cert.issuer().to_string(),
+        serial: cert.raw_serial_as_string(),
+        not_before: format_time(validity.not_before.to_datetime()),
+        not_after: format_time(not_after),
+        days_remaining: (not_after - OffsetDateTime::now_utc()).whole_days(),
+        subject_alt_names: sans_of(cert.extensions()),
+        public_key: key_info(cert.public_key()),
+        is_ca: cert.is_ca(),
+        extensions: cert.extensions().iter().map(extension_info).collect(),
+        sha256_fingerprint: fingerprint(der),
+    }
+}
+
+fn csr_info(csr: &X509CertificationRequest) -> CsrInfo {
+    let info = &csr.certification_request_info;
+    let extensions: Vec<&X509Extension> = info
+        .iter_attributes()
+        .filter_map(|attr| match attr.parsed_attribute() {
+            ParsedCriAttribute::ExtensionRequest(req) => Some(req.extensions.iter()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    CsrInfo {
+        subject: info.subject.to_string(),
+        subject_alt_names: sans_of(extensions.iter().copied()),
+        public_key: key_info(&info.subject_pki),
+        extensions: extensions.into_iter().map(extension_info).collect(),
+    }
+}
+
+fn converted_key_info(format: KeyFormat, name: &str, der: &[u8]) -> Result<Item> {
+    let pkcs8 = key::to_pkcs8_der(format, der, None)?;
+    let Item::PrivateKey(mut info) = pkcs8_key_info(&pkcs8)? else {
+        unreachable!()
+    };
+    info.format = name.to_string();
+    Ok(Item::PrivateKey(info))
+}
+
+fn pkcs8_key_info(der: &[u8]) -> Result<Item> {
+    let key_pair = KeyPair::try_from(der).context("Failed to parse private key")?;
+    let spki_der = key_pair.public_key_der();
+    let (_, spki) =
+        SubjectPublicKeyInfo::from_der(&spki_der).context("Failed to parse public key")?;
+
+    Ok(Item::PrivateKey(PrivateKeyInfo {
+        format: "PKCS#8".to_string(),
+        key: Some(key_info(&spki)),
+    }))
+}
+
+/// Describe the algorithm and size of a public key
+pub fn key_info(spki: &SubjectPublicKeyInfo) -> KeyInfo {
+    let alg_oid = spki.algorithm.algorithm.to_id_string();
+    let curve = spki
+        .algorithm
+        .parameters
+        .as_ref()
+        .and_then(|p| p.as_oid().ok())
+        .map(|oid| oid.to_id_string());
+
+    let algorithm = match (alg_oid.as_str(), curve.as_deref()) {
+        ("1.2.840.113549.1.1.1", _) => "RSA".to_string(),
+        ("1.2.840.113549.1.1.10", _) => "RSA-PSS".to_string(),
+        ("1.2.840.10045.2.1", Some("1.2.840.10045.3.1.7")) => "ECDSA P-256".to_string(),
+        ("1.2.840.10045.2.1", Some("1.3.132.0.34")) => "ECDSA P-384".to_string(),
+        ("1.2.840.10045.2.1", Some("1.3.132.0.35")) => "ECDSA P-521".to_string(),
+        ("1.2.840.10045.2.1", _) => "ECDSA".to_string(),
+        ("1.3.101.112", _) => "Ed25519".to_string(),
+        ("1.3.101.113", _) => "Ed448".to_string(),
+        (other, _) => other.to_string(),
+    };
+
+    let bits = match spki.parsed() {
+        Ok(PublicKey::RSA(rsa)) => {
+            // Strip the sign byte(s) before counting
+            let modulus: Vec<u8> = rsa
+                .modulus
+                .iter()
+                .copied()
+                .skip_while(|b| *b == 0)
+                .collect();
+            Some(modulus.len() * 8)
+        }
+        Ok(PublicKey::EC(ec)) => Some(ec.key_size()),
+        _ if alg_oid == "1.3.101.112" => Some(256),
+        _ => None,
+    };
+
+    KeyInfo { algorithm, bits }
+}
+
+fn sans_of<'a>(extensions: impl IntoIterator<Item = &'a X509Extension<'a>>) -> Vec<String> {
+    extensions
+        .into_iter()
+        .filter_map(|ext| match ext.parsed_extension() {
+            ParsedExtension::SubjectAlternativeName(san) => Some(
+                san.general_names
+                    .iter()
+                    .map(general_name_to_string)
+                    .collect::<Vec<_>>(),
+            ),
+            _ => None,
+        })
+        .flatten()
+        .collect()
+}
+
+fn general_name_to_string(name: &GeneralName) -> String {
+    match name {
+        GeneralName::DNSName(s) => format!("DNS:{}", s),
+        GeneralName::RFC822Name(s) => format!("email:{}", s),
+        GeneralName::URI(s) => format!("URI:{}", s),
+        GeneralName::IPAddress(bytes) => match bytes.len() {
+            4 => format!("IP:{}", IpAddr::from(<[u8; 4]>::try_from(*bytes).unwrap())),
+            16 => format!("IP:{}", IpAddr::from(<[u8; 16]>::try_from(*bytes).unwrap())),
+            _ => format!("IP:{}", hex_colon(bytes)),
+        },
+        GeneralName::OtherName(oid, _) => format!("othername:{}", oid.to_id_string()),
+        GeneralName::DirectoryName(dn) => format!("DirName:{}", dn),
+        GeneralName::RegisteredID(oid) => format!("RID:{}", oid.to_id_string()),
+        other => other.to_string(),
+    }
+}
+
+fn extension_info(ext: &X509Extension) -> ExtensionInfo {
+    let oid = ext.oid.to_id_string();
+    let name = oid2sn(&ext.oid, oid_registry())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|_| parsed_extension_name(ext.parsed_extension()).to_string());
+
+    ExtensionInfo {
+        name,
+        oid,
+        critical: ext.critical,
+        value: parsed_extension_value(ext.parsed_extension()),
+    }
+}
+
+fn parsed_extension_name(ext: &ParsedExtension) -> &'static str {
+    match ext {
+        ParsedExtension::AuthorityKeyIdentifier(_) => "authorityKeyIdentifier",
+        ParsedExtension::SubjectKeyIdentifier(_) => "subjectKeyIdentifier",
+        ParsedExtension::KeyUsage(_) => "keyUsage",
+        ParsedExtension::SubjectAlternativeName(_) => "subjectAltName",
+        ParsedExtension::BasicConstraints(_) => "basicConstraints",
+        ParsedExtension::ExtendedKeyUsage(_) => "extendedKeyUsage",
+        ParsedExtension::CRLDistributionPoints(_) => "crlDistributionPoints",
+        ParsedExtension::AuthorityInfoAccess(_) => "authorityInfoAccess",
+        ParsedExtension::NameConstraints(_) => "nameConstraints",
+        _ => "unknown",
+    }
+}
+
+fn parsed_extension_value(ext: &ParsedExtension) -> String {
+    match ext {
+        ParsedExtension::BasicConstraints(bc) => match bc.path_len_constraint {
+            Some(len) => format!("CA:{}, pathlen:{}", bc.ca, len),
+            None => format!("CA:{}", bc.ca),
+        },
+        ParsedExtension::KeyUsage(ku) => ku.to_string(),
+        ParsedExtension::ExtendedKeyUsage(eku) => {
+            let mut purposes = Vec::new();
+            if eku.any {
+                purposes.push("any".to_string());
+            }
+            if eku.server_auth {
+                purposes.push("serverAuth".to_string());
+            }
+            if eku.client_auth {
+                purposes.push("clientAuth".to_string());
+            }
+            if eku.code_signing {
+                purposes.push("codeSigning".to_string());
+            }
+            if eku.email_protection {
+                purposes.push("emailProtection".to_string());
+            }
+            if eku.time_stamping {
+                purposes.push("timeStamping".to_string());
+            }
+            if eku.ocsp_signing {
+                purposes.push("OCSPSigning".to_string());
+            }
+            purposes.extend(eku.other.iter().map(|oid| oid.to_id_string()));
+            purposes.join(", ")
+        }
+        ParsedExtension::SubjectKeyIdentifier(id) => hex_colon(id.0),
+        ParsedExtension::AuthorityKeyIdentifier(aki) => aki
+            .key_identifier
+            .as_ref()
+            .map(|id| hex_colon(id.0))
+            .unwrap_or_default(),
+        ParsedExtension::SubjectAlternativeName(san) => san
+            .general_names
+            .iter()
+            .map(general_name_to_string)
+            .collect::<Vec<_>>()
+            .join(", "),
+        ParsedExtension::AuthorityInfoAccess(aia) => aia
+            .iter()
+            .map(|desc| {
+                let method = match desc.access_method.to_id_string().as_str() {
+                    "1.3.6.1.5.5.7.48.1" => "OCSP".to_string(),
+                    "1.3.6.1.5.5.7.48.2" => "caIssuers".to_string(),
+                    other => other.to_string(),
+                };
+                format!(
+                    "{} - {}",
+                    method,
+                    general_name_to_string(&desc.access_location)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        ParsedExtension::CRLDistributionPoints(points) => points
+            .iter()
+            .filter_map(|p| match &p.distribution_point {
+                Some(x509_parser::extensions::DistributionPointName::FullName(names)) => Some(
+                    names
+                        .iter()
+                        .map(general_name_to_string)
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                ),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => String::new(),
+    }
+}
+
+/// SHA-256 fingerprint of DER bytes as colon-separated hex
+pub fn fingerprint(der: &[u8]) -> String {
+    hex_colon(&Sha256::digest(der))
+}
+
+fn hex_colon(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+fn format_time(dt: OffsetDateTime) -> String {
+    dt.format(&Rfc3339).unwrap_or_else(|_| dt.to_string())
+}
+
+fn print_item(item: &Item) {
+    let header = Style::new().bold();
+    let label = Style::new().cyan();
+
+    match item {
+        Item::Certificate(c) => {
+            println!("{}", header.apply_to(t("inspect_certificate")));
+            println!("  {} {}", label.apply_to(t("inspect_subject")), c.subject);
+            println!("  {} {}", label.apply_to(t("inspect_issuer")), c.issuer);
+            println!("  {} {}", label.apply_to(t("inspect_serial")), c.serial);
+            println!(
+                "  {} {}",
+                label.apply_to(t("inspect_not_before")),
+                c.not_before
+            );
+            let remaining = if c.days_remaining < 0 {
+                Style::new()
+                    .red()
+                    .apply_to(t("inspect_expired"))
+                    .to_string()
+            } else {
+                t("inspect_days_remaining").replace("{days}", &c.days_remaining.to_string())
+            };
+            println!(
+                "  {} {} ({})",
+                label.apply_to(t("inspect_not_after")),
+                c.not_after,
+                remaining
+            );
+            print_sans(&c.subject_alt_names);
+            print_key(&c.public_key);
+            println!(
+                "  {} {}",
+                label.apply_to(t("inspect_is_ca")),
+                t(if c.is_ca { "inspect_yes" } else { "inspect_no" })
+            );
+            print_extensions(&c.extensions);
+            println!(
+                "  {} {}",
+                label.apply_to(t("inspect_fingerprint")),
+                c.sha256_fingerprint
+            );
+        }
+        Item::CertificateRequest(r) => {
+            println!("{}", header.apply_to(t("inspect_csr")));
+            println!("  {} {}", label.apply_to(t("inspect_subject")), r.subject);
+            print_sans(&r.subject_alt_names);
+            print_key(&r.public_key);
+            print_extensions(&r.extensions);
+        }
+        Item::PrivateKey(k) => {
+            println!("{}", header.apply_to(t("inspect_private_key")));
+            println!("  {} {}", label.apply_to(t("inspect_format")), k.format);
+            if let Some(key) = &k.key {
+                print_key(key);
+            }
+        }
+    }
+}
+
+fn print_sans(sans: &[String]) {
+    if !sans.is_empty() {
+        println!(
+            "  {} {}",
+            Style::new().cyan().apply_to(t("inspect_sans")),
+            sans.join(", ")
+        );
+    }
+}
+
+fn print_key(key: &KeyInfo) {
+    println!(
+        "  {} {}",
+        Style::new().cyan().apply_to(t("inspect_key")),
+        key.description()
+    );
+}
+
+fn print_extensions(extensions: &[ExtensionInfo]) {
+    if extensions.is_empty() {
+        return;
+    }
+    println!(
+        "  {}",
+        Style::new().cyan().apply_to(t("inspect_extensions"))
+    );
+    for ext in extensions {
+        let critical = if ext.critical {
+            format!(" ({})", t("inspect_critical"))
+        } else {
+            String::new()
+        };
+        let name = extension_label(&ext.name);
+        if ext.value.is_empty() {
+            println!("    - {}{}", name, critical);
+        } else {
+            println!("    - {}{}: {}", name, critical, ext.value);
+        }
+    }
+}
+
+/// Display name of an extension; names without a translation are shown as-is
+fn extension_label(name: &str) -> String {
+    let key = match name {
+        "authorityKeyIdentifier" => "inspect_ext_authority_key_id",
+        "subjectKeyIdentifier" => "inspect_ext_subject_key_id",
+        "keyUsage" => "inspect_ext_key_usage",
+        "subjectAltName" => "inspect_ext_subject_alt_name",
+        "basicConstraints" => "inspect_ext_basic_constraints",
+        "extendedKeyUsage" => "inspect_ext_extended_key_usage",
+        "crlDistributionPoints" => "inspect_ext_crl_distribution_points",
+        "authorityInfoAccess" => "inspect_ext_authority_info_access",
+        "nameConstraints" => "inspect_ext_name_constraints",
+        "unknown" => "inspect_ext_unknown",
+        other => return other.to_string(),
+    };
+    t(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cert::{generate_cert, CertOptions};
+
+    fn generated() -> crate::cert::GeneratedCert {
+        generate_cert(CertOptions {
+            cn: "inspect.local".to_string(),
+            validity_days: 30,
+            pfx_password: "secret".to_string(),
+            ..CertOptions::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_inspect_pem_bundle() {
+        let cert = generated();
+        let bundle = format!("{}\n{}", cert.key_pem, cert.cert_pem);
+        let items = inspect_bytes(bundle.as_bytes(), Path::new("server.pem"), "").unwrap();
+        assert_eq!(items.len(), 2);
+
+        match &items[1] {
+            Item::Certificate(c) => {
+                assert_eq!(c.subject, "CN=inspect.local");
+                assert_eq!(
+                    c.subject_alt_names,
+                    vec!["DNS:inspect.local", "IP:127.0.0.1"]
+                );
+                assert_eq!(c.public_key.algorithm, "ECDSA P-256");
+                assert!(c.days_remaining >= 29);
+            }
+            other => panic!("expected certificate, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_inspect_pfx() {
+        let cert = generated();
+        assert!(inspect_bytes(&cert.pfx, Path::new("server.pfx"), "wrong").is_err());
+        let items = inspect_bytes(&cert.pfx, Path::new("server.pfx"), "secret").unwrap();
+        assert!(matches!(items[0], Item::Certificate(_)));
+        assert!(matches!(items[1], Item::PrivateKey(_)));
+
+        // Without the extension the PFX is recognised by its structure
+        assert!(looks_like_pfx(&cert.pfx));
+        assert!(!looks_like_pfx(&cert.cert_der));
+        let items = inspect_bytes(&cert.pfx, Path::new("server.bin"), "secret").unwrap();
+        assert_eq!(items.len(), 2);
+    }
+}
@@ -1,15 +1,26 @@
-use crate::cert::{CertOptions, KeyType};
+use crate::cert::{CertOptions, KeyType, DEFAULT_RSA_BITS, RSA_BITS};
 use crate::cli::Cli;
 use crate::config::Config;
 use crate::i18n::t;
-use inquire::{CustomType, Select, Text};
+use crate::san;
+use crate::usage::{self, Purpose};
+use inquire::validator::Validation;
+use inquire::{Confirm, CustomType, MultiSelect, Select, Text};
+use rcgen::{ExtendedKeyUsagePurpose, KeyUsagePurpose};
+use std::path::PathBuf;
 use std::process;
 
 /// Parse key type string from CLI to KeyType enum
-fn parse_key_type(s: &str) -> KeyType {
+pub fn parse_key_type(s: &str) -> KeyType {
     match s.to_lowercase().as_str() {
         "rsa" => KeyType::Rsa,
+        "rsa-sha384" => KeyType::RsaSha384,
+        "rsa-sha512" => KeyType::RsaSha512,
+        "rsa-pss-sha256" => KeyType::RsaPssSha256,
+        "rsa-pss-sha384" => KeyType::RsaPssSha384,
+        "rsa-pss-sha512" => KeyType::RsaPssSha512,
         "ecdsa" => KeyType::Ecdsa,
+        "ecdsa-p384" => KeyType::EcdsaP384,
         "ed25519" => KeyType::Ed25519,
         _ => KeyType::Ecdsa,
     }
@@ -43,20 +54,56 @@ pub fn resolve_options_with_config(cli: Cli, config: Option<Config>) -> CertOpti
     let merged_key_type = cli
         .key_type
         .or_else(|| config.as_ref().and_then(|c| c.key_type.clone()));
+    let merged_rsa_bits = cli
+        .rsa_bits
+        .or_else(|| config.as_ref().and_then(|c| c.rsa_bits));
+    let merged_email = cli
+        .email
+        .or_else(|| config.as_ref().and_then(|c| c.email.clone()));
+    let merged_uri = cli
+        .uri
+        .or_else(|| config.as_ref().and_then(|c| c.uri.clone()));
+    let merged_purpose = cli
+        .purpose
+        .or_else(|| config.as_ref().and_then(|c| c.purpose.clone()));
+    let merged_key_usage = cli
+        .key_usage
+        .or_else(|| config.as_ref().and_then(|c| c.key_usage.clone()));
+    let merged_extended_key_usage = cli
+        .extended_key_usage
+        .or_else(|| config.as_ref().and_then(|c| c.extended_key_usage.clone()));
+    let merged_key = cli.key.or_else(|| {
+        config
+            .as_ref()
+            .and_then(|c| c.key.as_ref().map(PathBuf::from))
+    });
+    let merged_formats = cli
+        .formats
+        .or_else(|| config.as_ref().and_then(|c| c.formats.clone()));
     let merged_pfx_password = cli
         .pfx_password
         .or_else(|| config.as_ref().and_then(|c| c.pfx_password.clone()));
 
     // Create a new Cli struct with merged values
     let merged_cli = Cli {
+        command: cli.command,
         cn: merged_cn,
         sans: merged_sans,
+        wildcard_apex: cli.wildcard_apex
+            || config
+                .as_ref()
+                .and_then(|c| c.wildcard_apex)
+                .unwrap_or(false),
+        auto_sans: cli.auto_sans || config.as_ref().and_then(|c| c.auto_sans).unwrap_or(false),
         days: merged_days,
         non_interactive: cli.non_interactive,
         default_settings: cli.default_settings,
         cmdlist: cli.cmdlist,
         full: cli.full,
         all: cli.all,
+        client: cli.client,
+        email: merged_email,
+        uri: merged_uri,
         output: cli.output,
         name: cli.name,
         pfx_password: merged_pfx_password,
@@ -67,9 +114,37 @@ pub fn resolve_options_with_config(cli: Cli, config: Option<Config>) -> CertOpti
         org: merged_org,
         org_unit: merged_org_unit,
         key_type: merged_key_type,
+        rsa_bits: merged_rsa_bits,
+        purpose: merged_purpose,
+        key_usage: merged_key_usage,
+        extended_key_usage: merged_extended_key_usage,
+        key: merged_key,
+        key_passphrase: cli.key_passphrase,
+        formats: merged_formats,
+        pfx_encryption: cli.pfx_encryption,
+        pfx_iterations: cli.pfx_iterations,
+        jks_password: cli.jks_password,
+        jks_alias: cli.jks_alias,
+        encrypt_key: cli.encrypt_key,
+        encrypt_key_kdf: cli.encrypt_key_kdf,
+        encrypt_key_passphrase_file: cli.encrypt_key_passphrase_file,
         verbose: cli.verbose,
         quiet: cli.quiet,
         config: cli.config,
+        ca: cli.ca,
+        ca_dir: cli.ca_dir,
+        inventory: cli.inventory,
+        intermediate: cli.intermediate,
+        ocsp_url: cli
+            .ocsp_url
+            .or_else(|| config.as_ref().and_then(|c| c.ocsp_url.clone())),
+        ca_issuers_url: cli
+            .ca_issuers_url
+            .or_else(|| config.as_ref().and_then(|c| c.ca_issuers_url.clone())),
+        crl_url: cli
+            .crl_url
+            .or_else(|| config.as_ref().and_then(|c| c.crl_url.clone())),
+        csr: cli.csr,
         lang: cli.lang,
     };
 
@@ -81,67 +156,114 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
         println!("{}", t("cmdlist_header"));
         println!("--full: Ask for Country, State, City, Org, OrgUnit");
         println!("--all: Ask for KeyType and all other fields");
+        println!("--client: Client certificate for mutual TLS (clientAuth, no DNS SAN)");
+        println!("--email, --uri: Client identity (subject emailAddress, email and URI SANs)");
         println!("--default_settings: Generate with defaults");
+        println!("--wildcard-apex: Also add the apex domain for each wildcard SAN");
+        println!("--auto-sans: Add the local hostname, localhost and interface addresses as SANs");
         println!("--non-interactive: Fail on missing args (or use defaults)");
         println!("--output, -o: Output directory");
         println!("--name, -n: Output file name (without extension)");
         println!("--pfx-password: Set PFX password");
+        println!("--formats: Output files (key, crt, pem, pfx, der, der-key, fullchain, csr, jks, truststore)");
+        println!("--pfx-encryption: PFX encryption (modern, legacy)");
+        println!("--pfx-iterations: PBKDF2/MAC iterations for modern PFX files");
+        println!("--jks-password: Password for .jks keystores and truststores");
+        println!("--jks-alias: Alias of the key entry in the .jks keystore");
         println!("--country, --state, --city, --org, --org-unit: DN fields");
-        println!("--key-type: Key algorithm (rsa, ecdsa, ed25519)");
+        println!("--key-type: Key algorithm and signature (rsa, rsa-sha384, rsa-sha512, rsa-pss-sha256, rsa-pss-sha384, rsa-pss-sha512, ecdsa, ecdsa-p384, ed25519)");
+        println!("--rsa-bits: RSA key size (2048, 3072, 4096)");
+        println!("--purpose: Certificate purpose for default key usages (server, client, code-signing, email)");
+        println!("--key-usage: Key Usage bits (e.g. digital-signature,key-encipherment)");
+        println!("--extended-key-usage, --eku: Extended Key Usages or custom OIDs (e.g. server-auth,client-auth)");
+        println!("--key: Use an existing private key instead of generating one");
+        println!("--key-passphrase: Passphrase for an encrypted --key");
+        println!("--encrypt-key: Write private keys encrypted (PKCS#8, AES-256-CBC)");
+        println!("--encrypt-key-kdf: Key derivation for --encrypt-key (pbkdf2, scrypt)");
+        println!("--encrypt-key-passphrase-file: Read the key encryption passphrase from a file");
         println!("--verbose, -v: Show verbose output");
         println!("--quiet, -q: Suppress non-essential output");
         println!("--config: Path to config file");
+        println!("--ca: Sign with the local CA instead of self-signing");
+        println!("--ca-dir: Local CA home directory");
+        println!("--intermediate: Sign with the named intermediate CA");
+        println!("--inventory: Inventory file of issued certificates");
+        println!("--ocsp-url, --ca-issuers-url: URLs to embed as Authority Information Access");
+        println!("--crl-url: CRL URL to embed as CRL Distribution Points");
+        println!("--csr: Write a certificate signing request (.csr) instead of a certificate");
+        println!("ca init: Create a local root CA");
+        println!("ca intermediate <name>: Create an intermediate CA");
+        println!("ca profile: Show or set the OCSP, CA Issuers and CRL URLs of a CA");
+        println!("sign <csr>: Issue a certificate for a CSR from the local CA");
+        println!("inspect <file>: Decode a certificate, CSR, key or PFX file (--json)");
+        println!("renew <name>: Renew a certificate, keeping its key");
+        println!("revoke <serial|name>: Revoke a certificate issued by the local CA (--reason)");
+        println!("ocsp-serve: Answer OCSP requests from the CA's revocation database (--listen, --delegated)");
+        println!("crl: Write a signed CRL for the local CA (PEM and DER, --next-update)");
+        println!("list: List issued certificates (--expiring-within 30d, --san <name>, --json)");
+        println!("search <query>: Search issued certificates by name, SAN, serial or fingerprint");
+        println!("check <path...>: Report expiring certificates (--warning 30d, --critical 7d; exit 1/2)");
         process::exit(0);
     }
 
-    let default_cn = "localhost".to_string();
-    let default_sans = vec!["127.0.0.1".to_string()];
+    // Client Mode: the subject names a client instead of a host
+    let is_client = cli.client;
+    let default_cn = if is_client { "client" } else { "localhost" }.to_string();
+    let default_sans = if is_client {
+        Vec::new()
+    } else {
+        vec!["127.0.0.1".to_string()]
+    };
     let default_days = 365;
     let default_key_type = KeyType::Ecdsa;
 
     // Parse key_type from CLI if provided
     let cli_key_type = cli.key_type.as_ref().map(|s| parse_key_type(s));
-
-    // Direct return if default_settings is requested
-    if cli.default_settings {
-        return CertOptions {
-            cn: cli.cn.unwrap_or(default_cn),
-            sans: cli.sans.unwrap_or(default_sans),
-            validity_days: cli.days.unwrap_or(default_days),
-            country: cli.country,
-            state: cli.state,
-            city: cli.city,
-            organization: cli.org,
-            org_unit: cli.org_unit,
-            key_type: cli_key_type.unwrap_or(default_key_type),
-            pfx_password: cli.pfx_password.unwrap_or_default(),
-        };
-    }
+    let purpose = cli
+        .purpose
+        .as_deref()
+        .map(usage::parse_purpose)
+        .unwrap_or(if is_client {
+            Purpose::Client
+        } else {
+            Purpose::Server
+        });
 
     // Determine mode
     let is_all = cli.all;
     let is_full = cli.full || is_all;
 
-    // Non-interactive fallback
-    if cli.non_interactive {
+    // Direct return for --default_settings and the non-interactive fallback
+    if cli.default_settings || cli.non_interactive {
         return CertOptions {
             cn: cli.cn.unwrap_or(default_cn),
-            sans: cli.sans.unwrap_or(default_sans),
+            sans: with_auto_sans(cli.sans.unwrap_or(default_sans), cli.auto_sans),
+            wildcard_apex: cli.wildcard_apex,
             validity_days: cli.days.unwrap_or(default_days),
             country: cli.country,
             state: cli.state,
             city: cli.city,
             organization: cli.org,
             org_unit: cli.org_unit,
+            email: cli.email,
+            uri: cli.uri,
             key_type: cli_key_type.unwrap_or(default_key_type),
+            rsa_bits: cli.rsa_bits.unwrap_or(DEFAULT_RSA_BITS),
+            purpose,
+            ocsp_url: cli.ocsp_url,
+            ca_issuers_url: cli.ca_issuers_url,
+            crl_url: cli.crl_url,
             pfx_password: cli.pfx_password.unwrap_or_default(),
+            ..CertOptions::default()
         };
     }
 
     // Interactive Mode
     if !cli.quiet {
         println!("{}", t("welcome"));
-        if !is_full && cli.cn.is_none() && cli.sans.is_none() && cli.days.is_none() {
+        if is_client {
+            println!("{}", t("client_mode"));
+        } else if !is_full && cli.cn.is_none() && cli.sans.is_none() && cli.days.is_none() {
             println!("{}", t("interactive_mode"));
         }
     }
@@ -149,17 +271,51 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
     // 1. Basic Fields
     let cn = match cli.cn {
         Some(v) => v,
+        None if is_client => Text::new(&t("enter_client_cn"))
+            .with_default(&default_cn)
+            .prompt()
+            .unwrap_or_else(|_| process::exit(0)),
         None => Text::new(&t("enter_cn"))
             .with_default(&default_cn)
+            .with_validator(|input: &str| {
+                Ok(match san::parse_host_cn(input) {
+                    Ok(_) => Validation::Valid,
+                    Err(e) => Validation::Invalid(e.to_string().into()),
+                })
+            })
             .prompt()
             .unwrap_or_else(|_| process::exit(0)),
     };
 
-    let sans = match cli.sans {
+    // Client Mode asks who the client is instead of host names
+    let (email, uri) = if is_client {
+        (
+            cli.email
+                .or_else(|| prompt_optional(&t("enter_client_email"))),
+            cli.uri.or_else(|| prompt_optional(&t("enter_client_uri"))),
+        )
+    } else {
+        (cli.email, cli.uri)
+    };
+
+    let mut sans = match cli.sans {
         Some(v) => v,
+        None if is_client => default_sans,
         None => {
+            // The loopback address is already offered in the --auto-sans list
             let input = Text::new(&t("enter_san"))
-                .with_default("127.0.0.1")
+                .with_default(if cli.auto_sans { "" } else { "127.0.0.1" })
+                .with_validator(|input: &str| {
+                    let error = input
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|s| !s.is_empty())
+                        .find_map(|s| san::parse_san(s).err());
+                    Ok(match error {
+                        Some(e) => Validation::Invalid(e.to_string().into()),
+                        None => Validation::Valid,
+                    })
+                })
                 .prompt()
                 .unwrap_or_else(|_| process::exit(0));
             input
@@ -170,6 +326,21 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
         }
     };
 
+    if cli.auto_sans {
+        add_sans(&mut sans, select_auto_sans());
+    }
+
+    // Offer the apex domain when a wildcard is requested
+    let has_wildcard = sans.iter().chain([&cn]).any(|s| s.contains("*."));
+    let wildcard_apex = if cli.wildcard_apex || !has_wildcard {
+        cli.wildcard_apex
+    } else {
+        Confirm::new(&t("confirm_wildcard_apex"))
+            .with_default(true)
+            .prompt()
+            .unwrap_or_else(|_| process::exit(0))
+    };
+
     let days = match cli.days {
         Some(v) => v,
         None => CustomType::<i64>::new(&t("enter_days"))
@@ -214,15 +385,30 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
     // 3. All Mode Fields - use CLI args or prompt
     let key_type = if let Some(kt) = cli_key_type {
         kt
-    } else if is_all {
-        let options = vec!["RSA", "ECDSA", "Ed25519"];
+    } else if is_all && cli.key.is_none() {
+        let options = vec![
+            "RSA (SHA-256)",
+            "RSA (SHA-384)",
+            "RSA (SHA-512)",
+            "RSA-PSS (SHA-256)",
+            "RSA-PSS (SHA-384)",
+            "RSA-PSS (SHA-512)",
+            "ECDSA P-256 (SHA-256)",
+            "ECDSA P-384 (SHA-384)",
+            "Ed25519",
+        ];
         let ans = Select::new(&t("select_key_type"), options)
             .prompt()
             .unwrap_or_else(|_| process::exit(0));
 
         match ans {
-            "RSA" => KeyType::Rsa,
-            "ECDSA" => KeyType::Ecdsa,
+            "RSA (SHA-256)" => KeyType::Rsa,
+            "RSA (SHA-384)" => KeyType::RsaSha384,
+            "RSA (SHA-512)" => KeyType::RsaSha512,
+            "RSA-PSS (SHA-256)" => KeyType::RsaPssSha256,
+            "RSA-PSS (SHA-384)" => KeyType::RsaPssSha384,
+            "RSA-PSS (SHA-512)" => KeyType::RsaPssSha512,
+            "ECDSA P-384 (SHA-384)" => KeyType::EcdsaP384,
             "Ed25519" => KeyType::Ed25519,
             _ => KeyType::Ecdsa,
         }
@@ -230,10 +416,39 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
         default_key_type
     };
 
-    // PFX Password - use CLI arg or prompt in All mode
+    let rsa_bits = if let Some(bits) = cli.rsa_bits {
+        bits
+    } else if is_all && key_type.is_rsa() {
+        Select::new(&t("select_rsa_bits"), RSA_BITS.to_vec())
+            .with_starting_cursor(0)
+            .prompt()
+            .unwrap_or_else(|_| process::exit(0))
+    } else {
+        DEFAULT_RSA_BITS
+    };
+
+    // Key Usage / Extended Key Usage - flags are applied by the caller, otherwise
+    // pick from the purpose defaults in All mode
+    let key_usages = if is_all && cli.key_usage.is_none() {
+        Some(select_key_usages(purpose, key_type))
+    } else {
+        None
+    };
+    let extended_key_usages = if is_all && cli.extended_key_usage.is_none() {
+        Some(select_extended_key_usages(purpose))
+    } else {
+        None
+    };
+
+    // PFX Password - use CLI arg or prompt in All and Client mode (only when a .pfx is written)
+    let writes_pfx = !cli.csr
+        && cli
+            .formats
+            .as_ref()
+            .is_none_or(|f| f.iter().any(|n| n.trim().eq_ignore_ascii_case("pfx")));
     let pfx_password = if let Some(pwd) = cli.pfx_password {
         pwd
-    } else if is_all {
+    } else if (is_all || is_client) && writes_pfx {
         prompt_optional(&t("enter_pfx_password")).unwrap_or_default()
     } else {
         String::new()
@@ -242,15 +457,119 @@ pub fn resolve_options(cli: Cli) -> CertOptions {
     CertOptions {
         cn,
         sans,
+        wildcard_apex,
         validity_days: days,
         country,
         state,
         city,
         organization,
         org_unit,
+        email,
+        uri,
         key_type,
+        rsa_bits,
+        purpose,
+        key_usages,
+        extended_key_usages,
+        ocsp_url: cli.ocsp_url,
+        ca_issuers_url: cli.ca_issuers_url,
+        crl_url: cli.crl_url,
         pfx_password,
+        ..CertOptions::default()
+    }
+}
+
+/// Append the local names for `--auto-sans` (all of them, without prompting)
+fn with_auto_sans(mut sans: Vec<String>, auto_sans: bool) -> Vec<String> {
+    if auto_sans {
+        add_sans(&mut sans, san::local_sans());
     }
+    sans
+}
+
+fn add_sans(sans: &mut Vec<String>, names: Vec<String>) {
+    for name in names {
+        if !sans.contains(&name) {
+            sans.push(name);
+        }
+    }
+}
+
+/// Checkbox list of the local names for `--auto-sans`, all pre-selected
+fn select_auto_sans() -> Vec<String> {
+    let names = san::local_sans();
+    let selected: Vec<usize> = (0..names.len()).collect();
+    MultiSelect::new(&t("select_auto_sans"), names)
+        .with_default(&selected)
+        .prompt()
+        .unwrap_or_else(|_| process::exit(0))
+}
+
+/// Multi-select of Key Usage bits, with the purpose defaults pre-selected
+fn select_key_usages(purpose: Purpose, key_type: KeyType) -> Vec<KeyUsagePurpose> {
+    let defaults = usage::default_key_usages(purpose, key_type);
+    let options: Vec<&str> = usage::KEY_USAGES.iter().map(|(name, _)| *name).collect();
+    let selected: Vec<usize> = usage::KEY_USAGES
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, u))| defaults.contains(u))
+        .map(|(i, _)| i)
+        .collect();
+    let names = MultiSelect::new(&t("select_key_usage"), options)
+        .with_default(&selected)
+        .prompt()
+        .unwrap_or_else(|_| process::exit(0));
+    usage::KEY_USAGES
+        .iter()
+        .filter(|(name, _)| names.contains(name))
+        .map(|(_, u)| *u)
+        .collect()
+}
+
+/// Multi-select of Extended Key Usages, with the purpose defaults pre-selected,
+/// followed by optional custom OIDs
+fn select_extended_key_usages(purpose: Purpose) -> Vec<ExtendedKeyUsagePurpose> {
+    let defaults = usage::default_extended_key_usages(purpose);
+    let options: Vec<&str> = usage::EXTENDED_KEY_USAGES
+        .iter()
+        .map(|(name, _)| *name)
+        .collect();
+    let selected: Vec<usize> = usage::EXTENDED_KEY_USAGES
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, u))| defaults.contains(u))
+        .map(|(i, _)| i)
+        .collect();
+    let names = MultiSelect::new(&t("select_extended_key_usage"), options)
+        .with_default(&selected)
+        .prompt()
+        .unwrap_or_else(|_| process::exit(0));
+    let mut usages: Vec<ExtendedKeyUsagePurpose> = usage::EXTENDED_KEY_USAGES
+        .iter()
+        .filter(|(name, _)| names.contains(name))
+        .map(|(_, u)| u.clone())
+        .collect();
+
+    let custom = Text::new(&t("enter_custom_eku"))
+        .with_validator(|input: &str| {
+            let invalid = input
+                .split(',')
+                .map(str::trim)
+                .find(|s| !s.is_empty() && usage::parse_oid(s).is_none());
+            Ok(match invalid {
+                Some(s) => Validation::Invalid(format!("Invalid OID: {}", s).into()),
+                None => Validation::Valid,
+            })
+        })
+        .prompt()
+        .unwrap_or_default();
+    usages.extend(
+        custom
+            .split(',')
+            .filter_map(|s| usage::parse_oid(s.trim()))
+            .map(ExtendedKeyUsagePurpose::Other),
+    );
+    usages
 }
 
 fn prompt_optional(msg: &str) -> Option<String> {
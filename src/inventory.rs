@@ -0,0 +1,407 @@
+use crate::cli::{InventoryFilter, ListArgs, SearchArgs};
+use crate::crl;
+use crate::i18n::t;
+use crate::inspect;
+use anyhow::{bail, Context, Result};
+use console::Style;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+use x509_parser::prelude::{FromDer, X509Certificate};
+
+/// File name of the inventory inside the SureSign data directory
+pub const INVENTORY_FILE: &str = "inventory.json";
+
+/// Default inventory location (`<data dir>/suresign/inventory.json`)
+pub fn default_path() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join("suresign")
+        .join(INVENTORY_FILE)
+}
+
+/// Every certificate SureSign has issued, oldest first
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Inventory {
+    #[serde(default)]
+    pub certificates: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Entry {
+    /// Output file name (without extension)
+    pub name: String,
+    /// Serial number as lowercase hex without leading zero bytes
+    pub serial: String,
+    pub subject: String,
+    pub issuer: String,
+    pub subject_alt_names: Vec<String>,
+    pub key_type: String,
+    pub sha256_fingerprint: String,
+    /// Absolute path of the certificate file as written
+    pub path: String,
+    pub not_after: String,
+    /// When the certificate was recorded (RFC 3339)
+    pub issued_at: String,
+}
+
+impl Inventory {
+    /// Load the inventory (empty if nothing was issued yet)
+    pub fn load(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = fs::read_to_string(path).context("Failed to read inventory")?;
+        serde_json::from_str(&content).context("Failed to parse inventory")
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create inventory directory")?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path, content + "\n").context("Failed to write inventory")
+    }
+
+    /// Add an entry, replacing an earlier record of the same certificate
+    pub fn add(&mut self, entry: Entry) {
+        self.certificates
+            .retain(|e| e.sha256_fingerprint != entry.sha256_fingerprint);
+        self.certificates.push(entry);
+    }
+
+    /// Entries whose file has not since been overwritten by a newer certificate
+    fn current(&self) -> impl Iterator<Item = &Entry> {
+        self.certificates.iter().enumerate().filter_map(|(i, e)| {
+            let superseded = self.certificates[i + 1..].iter().any(|n| n.path == e.path);
+            (!superseded).then_some(e)
+        })
+    }
+}
+
+impl Entry {
+    /// Describe a DER certificate written to `path`
+    pub fn new(name: &str, path: &Path, der: &[u8]) -> Result<Self> {
+        let (_, x509) = X509Certificate::from_der(der)
+            .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+        let info = inspect::cert_info(&x509, der);
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let now = OffsetDateTime::now_utc().replace_nanosecond(0)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            serial: crl::serial_of(x509.raw_serial()),
+            subject: info.subject,
+            issuer: info.issuer,
+            subject_alt_names: info.subject_alt_names,
+            key_type: info.public_key.description(),
+            sha256_fingerprint: info.sha256_fingerprint,
+            path: path.display().to_string(),
+            not_after: info.not_after,
+            issued_at: now.format(&Rfc3339)?,
+        })
+    }
+
+    fn expires(&self) -> Option<OffsetDateTime> {
+        OffsetDateTime::parse(&self.not_after, &Rfc3339).ok()
+    }
+
+    /// Whether one of the SANs is `name` or a wildcard covering it
+    fn has_san(&self, name: &str) -> bool {
+        let name = san_value(name).trim_end_matches('.').to_lowercase();
+        self.subject_alt_names.iter().any(|san| {
+            let san = san_value(san).to_lowercase();
+            if let (Ok(a), Ok(b)) = (san.parse::<IpAddr>(), name.parse::<IpAddr>()) {
+                return a == b;
+            }
+            match san.strip_prefix("*.") {
+                Some(parent) => {
+                    san == name || name.split_once('.').is_some_and(|(_, rest)| rest == parent)
+                }
+                None => san == name,
+            }
+        })
+    }
+
+    /// Case-insensitive match on names, subject, SANs or path, or a serial or
+    /// fingerprint prefix
+    fn matches(&self, query: &str) -> bool {
+        let query = query.to_lowercase();
+        let text = [&self.name, &self.subject, &self.issuer, &self.path]
+            .into_iter()
+            .chain(&self.subject_alt_names)
+            .any(|field| field.to_lowercase().contains(&query));
+        // Serials and fingerprints match by prefix, with or without colons
+        let hex: String = query.chars().filter(|c| *c != ':').collect();
+        let fingerprint = self.sha256_fingerprint.replace(':', "").to_lowercase();
+        text || (!hex.is_empty()
+            && (self.serial.starts_with(&hex) || fingerprint.starts_with(&hex)))
+    }
+}
+
+/// SAN value without its `DNS:`/`IP:`/... type prefix
+fn san_value(san: &str) -> &str {
+    ["DNS:", "IP:", "email:", "URI:"]
+        .iter()
+        .find_map(|prefix| san.strip_prefix(prefix))
+        .unwrap_or(san)
+}
+
+/// Parse a duration such as `30d`, `12h` or `2w` (a bare number is days)
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+    let (number, unit) = match value.char_indices().last() {
+        Some((i, c)) if c.is_ascii_alphabetic() => (&value[..i], c.to_ascii_lowercase()),
+        _ => (value, 'd'),
+    };
+    let Ok(n) = number.parse::<i64>() else {
+        bail!(
+            "Invalid duration '{}' (expected e.g. 30d, 12h or 2w)",
+            value
+        );
+    };
+    match unit {
+        'h' => Ok(Duration::hours(n)),
+        'd' => Ok(Duration::days(n)),
+        'w' => Ok(Duration::weeks(n)),
+        _ => bail!(
+            "Invalid duration '{}' (expected e.g. 30d, 12h or 2w)",
+            value
+        ),
+    }
+}
+
+/// Record a newly written certificate; a broken inventory only warns, since
+/// the files are already on disk
+pub fn record(inventory: &Path, name: &str, cert_path: &Path, der: &[u8]) {
+    let result = Entry::new(name, cert_path, der).and_then(|entry| {
+        let mut db = Inventory::load(inventory)?;
+        db.add(entry);
+        db.save(inventory)
+    });
+    if let Err(e) = result {
+        eprintln!(
+            "{}",
+            Style::new()
+                .yellow()
+                .apply_to(t("inventory_failed").replace("{error}", &format!("{:#}", e)))
+        );
+    }
+}
+
+/// Handle `suresign list`
+pub fn run_list(args: ListArgs, inventory: &Path) -> Result<()> {
+    show(&Inventory::load(inventory)?, None, &args.filter)
+}
+
+/// Handle `suresign search <query>`
+pub fn run_search(args: SearchArgs, inventory: &Path) -> Result<()> {
+    show(
+        &Inventory::load(inventory)?,
+        Some(&args.query),
+        &args.filter,
+    )
+}
+
+fn show(db: &Inventory, query: Option<&str>, filter: &InventoryFilter) -> Result<()> {
+    let entries = select(db, query, filter, OffsetDateTime::now_utc())?;
+
+    if filter.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+    if entries.is_empty() {
+        println!("{}", t("inventory_empty"));
+        return Ok(());
+    }
+    for (i, entry) in entries.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        print_entry(entry);
+    }
+    Ok(())
+}
+
+fn select<'a>(
+    db: &'a Inventory,
+    query: Option<&str>,
+    filter: &InventoryFilter,
+    now: OffsetDateTime,
+) -> Result<Vec<&'a Entry>> {
+    let deadline = match &filter.expiring_within {
+        Some(within) => Some(now + parse_duration(within)?),
+        None => None,
+    };
+    let entries: Box<dyn Iterator<Item = &Entry>> = if filter.all {
+        Box::new(db.certificates.iter())
+    } else {
+        Box::new(db.current())
+    };
+
+    Ok(entries
+        .filter(|e| query.is_none_or(|q| e.matches(q)))
+        .filter(|e| filter.san.as_deref().is_none_or(|san| e.has_san(san)))
+        .filter(|e| deadline.is_none_or(|d| e.expires().is_some_and(|x| x <= d)))
+        .collect())
+}
+
+fn print_entry(entry: &Entry) {
+    let label = Style::new().cyan();
+
+    println!(
+        "{} {}",
+        Style::new().bold().apply_to(&entry.name),
+        Style::new().dim().apply_to(&entry.path)
+    );
+    println!(
+        "  {} {}",
+        label.apply_to(t("inspect_subject")),
+        entry.subject
+    );
+    println!("  {} {}", label.apply_to(t("inspect_issuer")), entry.issuer);
+    println!("  {} {}", label.apply_to(t("inspect_serial")), entry.serial);
+    let remaining = match entry.expires() {
+        Some(expires) => {
+            let days = (expires - OffsetDateTime::now_utc()).whole_days();
+            if expires < OffsetDateTime::now_utc() {
+                Style::new()
+                    .red()
+                    .apply_to(t("inspect_expired"))
+                    .to_string()
+            } else {
+                let text = t("inspect_days_remaining").replace("{days}", &days.to_string());
+                if days < 30 {
+                    Style::new().yellow().apply_to(text).to_string()
+                } else {
+                    text
+                }
+            }
+        }
+        None => String::new(),
+    };
+    println!(
+        "  {} {} ({})",
+        label.apply_to(t("inspect_not_after")),
+        entry.not_after,
+        remaining
+    );
+    if !entry.subject_alt_names.is_empty() {
+        println!(
+            "  {} {}",
+            label.apply_to(t("inspect_sans")),
+            entry.subject_alt_names.join(", ")
+        );
+    }
+    println!("  {} {}", label.apply_to(t("inspect_key")), entry.key_type);
+    println!(
+        "  {} {}",
+        label.apply_to(t("inspect_fingerprint")),
+        entry.sha256_fingerprint
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str, path: &str, sans: &[&str], not_after: &str) -> Entry {
+        Entry {
+            name: name.to_string(),
+            serial: "4a1b".to_string(),
+            subject: format!("CN={}", name),
+            issuer: "CN=SureSign Local CA".to_string(),
+            subject_alt_names: sans.iter().map(|s| s.to_string()).collect(),
+            key_type: "ECDSA P-256 (256 bits)".to_string(),
+            sha256_fingerprint: format!("AB:CD:{}", &not_after[..4]),
+            path: path.to_string(),
+            not_after: not_after.to_string(),
+            issued_at: "2026-01-01T00:00:00Z".to_string(),
+        }
+    }
+
+    fn filter() -> InventoryFilter {
+        InventoryFilter {
+            expiring_within: None,
+            san: None,
+            all: false,
+            json: false,
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("30d").unwrap(), Duration::days(30));
+        assert_eq!(parse_duration("12h").unwrap(), Duration::hours(12));
+        assert_eq!(parse_duration("2W").unwrap(), Duration::weeks(2));
+        assert_eq!(parse_duration("90").unwrap(), Duration::days(90));
+        assert!(parse_duration("30m").is_err());
+        assert!(parse_duration("d").is_err());
+    }
+
+    #[test]
+    fn test_select_filters() {
+        let mut db = Inventory::default();
+        db.add(entry(
+            "api",
+            "/srv/api.crt",
+            &["DNS:api.local", "IP:127.0.0.1"],
+            "2026-01-20T00:00:00Z",
+        ));
+        db.add(entry(
+            "web",
+            "/srv/web.crt",
+            &["DNS:*.dev.local"],
+            "2027-01-01T00:00:00Z",
+        ));
+        // Renewed api certificate written over the first one
+        let mut renewed = entry(
+            "api",
+            "/srv/api.crt",
+            &["DNS:api.local"],
+            "2027-06-01T00:00:00Z",
+        );
+        renewed.sha256_fingerprint = "EF:01".to_string();
+        db.add(renewed);
+        let now = OffsetDateTime::parse("2026-01-01T00:00:00Z", &Rfc3339).unwrap();
+        let names = |entries: Vec<&Entry>| -> Vec<String> {
+            entries
+                .iter()
+                .map(|e| e.not_after[..4].to_string() + &e.name)
+                .collect()
+        };
+
+        let all = select(&db, None, &filter(), now).unwrap();
+        assert_eq!(names(all), ["2027web", "2027api"]);
+
+        let f = InventoryFilter {
+            all: true,
+            expiring_within: Some("30d".to_string()),
+            ..filter()
+        };
+        assert_eq!(names(select(&db, None, &f, now).unwrap()), ["2026api"]);
+
+        let f = InventoryFilter {
+            san: Some("App.Dev.Local".to_string()),
+            ..filter()
+        };
+        assert_eq!(names(select(&db, None, &f, now).unwrap()), ["2027web"]);
+        let f = InventoryFilter {
+            san: Some("a.b.dev.local".to_string()),
+            ..filter()
+        };
+        assert!(select(&db, None, &f, now).unwrap().is_empty());
+
+        assert_eq!(
+            names(select(&db, Some("ef01"), &filter(), now).unwrap()),
+            ["2027api"]
+        );
+        assert_eq!(
+            names(select(&db, Some("/srv/web"), &filter(), now).unwrap()),
+            ["2027web"]
+        );
+    }
+}
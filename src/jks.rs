@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use pkcs8::der::asn1::{AnyRef, OctetStringRef};
+use pkcs8::der::{Encode, Header, Length, Tag};
+use pkcs8::{AlgorithmIdentifierRef, ObjectIdentifier};
+use sha1::{Digest, Sha1};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Java KeyStore (JKS) writer, following the format of sun.security.provider.JavaKeyStore
+
+const MAGIC: u32 = 0xFEED_FEED;
+const VERSION: u32 = 2;
+const TAG_PRIVATE_KEY: u32 = 1;
+const TAG_TRUSTED_CERT: u32 = 2;
+
+/// Sun's proprietary key protection algorithm (sun.security.provider.KeyProtector)
+const KEY_PROTECTOR: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.42.2.17.1.1");
+
+/// Mixed into the keystore integrity digest by the JDK
+const WHITENER: &[u8] = b"Mighty Aphrodite";
+
+/// Password used for JKS files when none is given (the JDK's truststore default)
+pub const DEFAULT_PASSWORD: &str = "changeit";
+
+/// Build a keystore with one private key entry and its certificate chain (leaf first)
+pub fn keystore(
+    alias: &str,
+    key_der: &[u8],
+    chain_der: &[&[u8]],
+    password: &str,
+) -> Result<Vec<u8>> {
+    let mut out = header(1);
+    out.extend(TAG_PRIVATE_KEY.to_be_bytes());
+    write_utf(&mut out, &alias.to_lowercase())?;
+    out.extend(timestamp().to_be_bytes());
+
+    let protected = protect_key(key_der, password)?;
+    write_bytes(&mut out, &protected)?;
+
+    out.extend(u32::try_from(chain_der.len())?.to_be_bytes());
+    for cert in chain_der {
+        write_utf(&mut out, "X.509")?;
+        write_bytes(&mut out, cert)?;
+    }
+
+    Ok(seal(out, password))
+}
+
+/// Build a truststore holding the given certificates as trusted entries
+pub fn truststore(entries: &[(&str, &[u8])], password: &str) -> Result<Vec<u8>> {
+    let mut out = header(u32::try_from(entries.len())?);
+    for (alias, cert) in entries {
+        out.extend(TAG_TRUSTED_CERT.to_be_bytes());
+        write_utf(&mut out, &alias.to_lowercase())?;
+        out.extend(timestamp().to_be_bytes());
+        write_utf(&mut out, "X.509")?;
+        write_bytes(&mut out, cert)?;
+    }
+
+    Ok(seal(out, password))
+}
+
+fn header(count: u32) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend(MAGIC.to_be_bytes());
+    out.extend(VERSION.to_be_bytes());
+    out.extend(count.to_be_bytes());
+    out
+}
+
+fn timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Java's DataOutput.writeUTF (aliases are ASCII in practice, so plain UTF-8 suffices)
+fn write_utf(out: &mut Vec<u8>, s: &str) -> Result<()> {
+    let len = u16::try_from(s.len()).context("JKS alias too long")?;
+    out.extend(len.to_be_bytes());
+    out.extend(s.as_bytes());
+    Ok(())
+}
+
+fn write_bytes(out: &mut Vec<u8>, data: &[u8]) -> Result<()> {
+    out.extend(u32::try_from(data.len())?.to_be_bytes());
+    out.extend(data);
+    Ok(())
+}
+
+/// Java passwords are hashed as UTF-16BE code units
+fn password_bytes(password: &str) -> Vec<u8> {
+    password
+        .encode_utf16()
+        .flat_map(|c| c.to_be_bytes())
+        .collect()
+}
+
+/// Append the integrity digest: SHA-1(password, whitener, keystore bytes)
+fn seal(mut data: Vec<u8>, password: &str) -> Vec<u8> {
+    let digest = Sha1::new()
+        .chain_update(password_bytes(password))
+        .chain_update(WHITENER)
+        .chain_update(&data)
+        .finalize();
+    data.extend(digest);
+    data
+}
+
+/// Encrypt a PKCS#8 key with the JDK KeyProtector scheme and wrap it in an
+/// EncryptedPrivateKeyInfo
+fn protect_key(key_der: &[u8], password: &str) -> Result<Vec<u8>> {
+    let passwd = password_bytes(password);
+
+    let mut salt = [0u8; 20];
+    getrandom::getrandom(&mut salt)
+        .map_err(|e| anyhow::anyhow!("Failed to generate salt: {}", e))?;
+
+    let mut protected = salt.to_vec();
+    protected.extend(xor_keystream(&passwd, &salt, key_der));
+    protected.extend(
+        Sha1::new()
+            .chain_update(&passwd)
+            .chain_update(key_der)
+            .finalize(),
+    );
+
+    let algorithm = AlgorithmIdentifierRef {
+        oid: KEY_PROTECTOR,
+        parameters: Some(AnyRef::NULL),
+    }
+    .to_der()
+    .map_err(|e| anyhow::anyhow!("Failed to encode JKS key: {}", e))?;
+    let data = OctetStringRef::new(&protected)
+        .and_then(|o| o.to_der())
+        .map_err(|e| anyhow::anyhow!("Failed to encode JKS key: {}", e))?;
+
+    let body = [algorithm, data].concat();
+    let mut out = Length::try_from(body.len())
+        .and_then(|len| Header::new(Tag::Sequence, len))
+        .and_then(|h| h.to_der())
+        .map_err(|e| anyhow::anyhow!("Failed to encode JKS key: {}", e))?;
+    out.extend(body);
+    Ok(out)
+}
+
+/// XOR data with the KeyProtector keystream: SHA-1(password, previous block),
+/// seeded with the salt
+fn xor_keystream(passwd: &[u8], salt: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut keystream = Vec::with_capacity(data.len() + 20);
+    let mut block = salt.to_vec();
+    while keystream.len() < data.len() {
+        block = Sha1::new()
+            .chain_update(passwd)
+            .chain_update(&block)
+            .finalize()
+            .to_vec();
+        keystream.extend(&block);
+    }
+    data.iter().zip(&keystream).map(|(d, k)| d ^ k).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystore_digest_and_key_protection() {
+        let key = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let key_der = key.serialize_der();
+        let cert = rcgen::CertificateParams::new(vec!["jks.local".to_string()])
+            .unwrap()
+            .self_signed(&key)
+            .unwrap();
+        let store = keystore("Server", &key_der, &[cert.der()], "storepass").unwrap();
+
+        assert_eq!(&store[..4], &MAGIC.to_be_bytes());
+        let body = &store[..store.len() - 20];
+        assert_eq!(seal(body.to_vec(), "storepass"), store);
+        assert_ne!(seal(body.to_vec(), "wrong"), store);
+
+        // Entry: tag, alias, timestamp, then the protected key
+        let alias_end = 12 + 4 + 2 + "server".len();
+        assert_eq!(&store[18..alias_end], b"server");
+        let len_at = alias_end + 8;
+        let len = u32::from_be_bytes(store[len_at..len_at + 4].try_into().unwrap()) as usize;
+        let protected = &store[len_at + 4..len_at + 4 + len];
+
+        // salt || encrypted key || check digest, at the end of the OCTET STRING
+        let inner = &protected[protected.len() - (20 + key_der.len() + 20)..];
+        let (salt, rest) = inner.split_at(20);
+        let (encrypted, check) = rest.split_at(key_der.len());
+        let passwd = password_bytes("storepass");
+        let plain = xor_keystream(&passwd, salt, encrypted);
+        assert_eq!(plain, key_der);
+        let expected = Sha1::new()
+            .chain_update(&passwd)
+            .chain_update(&plain)
+            .finalize();
+        assert_eq!(check, expected.as_slice());
+    }
+}
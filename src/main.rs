@@ -1,19 +1,34 @@
+mod ca;
 mod cert;
+mod check;
 mod cli;
 mod config;
+mod crl;
 mod i18n;
+mod inspect;
 mod interactive;
+mod inventory;
+mod jks;
+mod key;
+mod ocsp;
+mod output;
+mod pss;
+mod renew;
+mod san;
+mod sign;
+mod usage;
 
 use crate::config::Config;
 use crate::i18n::t;
-use anyhow::{Context, Result};
+use crate::output::Format;
+use anyhow::{bail, Context, Result};
 use console::Style;
 use std::fs;
-use std::io::{self, Write};
 use std::path::{Path, PathBuf};
+use std::process;
 
 fn main() -> Result<()> {
-    let args = cli::parse();
+    let mut args = cli::parse();
 
     // Store verbose/quiet flags before moving args
     let verbose = args.verbose;
@@ -34,11 +49,92 @@ fn main() -> Result<()> {
             .and_then(|c| c.output.as_ref().map(PathBuf::from))
     });
 
+    let ca_dir: PathBuf = args
+        .ca_dir
+        .clone()
+        .or_else(|| {
+            config
+                .as_ref()
+                .and_then(|c| c.ca_dir.as_ref().map(PathBuf::from))
+        })
+        .unwrap_or_else(ca::default_ca_dir);
+
+    let inventory_path: PathBuf = args
+        .inventory
+        .clone()
+        .or_else(|| {
+            config
+                .as_ref()
+                .and_then(|c| c.inventory.as_ref().map(PathBuf::from))
+        })
+        .unwrap_or_else(inventory::default_path);
+
+    let pfx_iterations = args
+        .pfx_iterations
+        .or_else(|| config.as_ref().and_then(|c| c.pfx_iterations))
+        .unwrap_or(cert::DEFAULT_PFX_ITERATIONS);
+    // The flag is range-checked by clap; the config value is checked here
+    if pfx_iterations == 0 {
+        bail!("pfx_iterations must be at least 1");
+    }
+    let pfx_format = match args
+        .pfx_encryption
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.pfx_encryption.clone()))
+        .as_deref()
+    {
+        Some("legacy") => cert::PfxFormat::Legacy,
+        _ => cert::PfxFormat::Modern {
+            iterations: pfx_iterations,
+        },
+    };
+
+    if let Some(command) = args.command.take() {
+        return match command {
+            cli::Command::Ca { action } => ca::run(action, &ca_dir, quiet),
+            cli::Command::Sign(sign_args) => sign::run(sign_args, &ca_dir, &inventory_path, quiet),
+            cli::Command::Inspect(inspect_args) => inspect::run(inspect_args),
+            cli::Command::Renew(renew_args) => renew::run(
+                renew_args,
+                output_dir,
+                config.as_ref().and_then(|c| c.pfx_password.clone()),
+                pfx_format,
+                &ca_dir,
+                &inventory_path,
+                quiet,
+            ),
+            cli::Command::Revoke(revoke_args) => {
+                crl::run_revoke(revoke_args, output_dir, &ca_dir, quiet)
+            }
+            cli::Command::Crl(crl_args) => crl::run_crl(crl_args, &ca_dir, quiet),
+            cli::Command::OcspServe(ocsp_args) => ocsp::run(ocsp_args, &ca_dir, quiet),
+            cli::Command::List(list_args) => inventory::run_list(list_args, &inventory_path),
+            cli::Command::Search(search_args) => {
+                inventory::run_search(search_args, &inventory_path)
+            }
+            // Exit codes are the result, so errors get their own code
+            cli::Command::Check(check_args) => match check::run(check_args, quiet) {
+                Ok(code) => process::exit(code),
+                Err(e) => {
+                    eprintln!("Error: {:?}", e);
+                    process::exit(check::EXIT_UNKNOWN)
+                }
+            },
+        };
+    }
+
+    let intermediate = args
+        .intermediate
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.intermediate.clone()));
+    let use_ca =
+        args.ca || intermediate.is_some() || config.as_ref().and_then(|c| c.ca).unwrap_or(false);
+
     let file_name = args
         .name
         .clone()
         .or_else(|| config.as_ref().and_then(|c| c.name.clone()))
-        .unwrap_or_else(|| "server".to_string());
+        .unwrap_or_else(|| if args.client { "client" } else { "server" }.to_string());
 
     // Log config file usage
     if verbose {
@@ -49,10 +145,188 @@ fn main() -> Result<()> {
         }
     }
 
-    let opts = interactive::resolve_options_with_config(args, config);
+    // Existing key to certify instead of generating a new one
+    let key_file = args.key.clone().or_else(|| {
+        config
+            .as_ref()
+            .and_then(|c| c.key.as_ref().map(PathBuf::from))
+    });
+    let existing_key = match &key_file {
+        Some(path) => {
+            let data = fs::read(path)
+                .with_context(|| format!("Failed to read key file {}", path.display()))?;
+            let passphrase =
+                key::resolve_passphrase(&data, args.key_passphrase.clone(), !args.non_interactive);
+            let key_pair = key::load_private_key(&data, passphrase.as_deref())
+                .with_context(|| format!("Failed to load private key {}", path.display()))?;
+            Some(key_pair)
+        }
+        None => None,
+    };
+
+    // Passphrase-encrypted key output
+    let encrypt_key =
+        args.encrypt_key || config.as_ref().and_then(|c| c.encrypt_key).unwrap_or(false);
+    let key_encryption = if encrypt_key {
+        let kdf = args
+            .encrypt_key_kdf
+            .clone()
+            .or_else(|| config.as_ref().and_then(|c| c.encrypt_key_kdf.clone()))
+            .map(|s| key::parse_kdf(&s))
+            .unwrap_or(key::Kdf::Pbkdf2);
+        let passphrase_file = args.encrypt_key_passphrase_file.clone().or_else(|| {
+            config
+                .as_ref()
+                .and_then(|c| c.encrypt_key_passphrase_file.as_ref().map(PathBuf::from))
+        });
+        let passphrase =
+            key::resolve_encrypt_passphrase(passphrase_file.as_deref(), !args.non_interactive)?;
+        Some((passphrase, kdf))
+    } else {
+        None
+    };
+
+    let explicit_key_type = args
+        .key_type
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.key_type.clone()))
+        .map(|s| interactive::parse_key_type(&s));
+    let formats = match args
+        .formats
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.formats.clone()))
+    {
+        Some(names) => Some(output::parse_formats(&names)?),
+        None => None,
+    };
+    let key_usages = match args
+        .key_usage
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.key_usage.clone()))
+    {
+        Some(names) => Some(usage::parse_key_usages(&names)?),
+        None => None,
+    };
+    let extended_key_usages = match args
+        .extended_key_usage
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.extended_key_usage.clone()))
+    {
+        Some(names) => Some(usage::parse_extended_key_usages(&names)?),
+        None => None,
+    };
+    let csr_mode = args.csr || formats.as_ref().is_some_and(|f| f.contains(&Format::Csr));
+    if csr_mode && use_ca {
+        bail!("CSR mode cannot be combined with --ca or --intermediate (sign the CSR with `suresign sign`)");
+    }
+    if let Some(formats) = &formats {
+        if csr_mode {
+            if let Some(format) = formats.iter().find(|f| !f.in_csr_mode()) {
+                bail!(
+                    "The {} format cannot be written in CSR mode (use key, der-key and csr)",
+                    format.name()
+                );
+            }
+        }
+    }
+    let jks_password = args
+        .jks_password
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.jks_password.clone()));
+    let jks_alias = args
+        .jks_alias
+        .clone()
+        .or_else(|| config.as_ref().and_then(|c| c.jks_alias.clone()))
+        .unwrap_or_else(|| file_name.clone());
+    let mut opts = interactive::resolve_options_with_config(args, config);
+    // JKS files always need a password; reuse the PFX one when set
+    let jks_password = jks_password.unwrap_or_else(|| {
+        if opts.pfx_password.is_empty() {
+            jks::DEFAULT_PASSWORD.to_string()
+        } else {
+            opts.pfx_password.clone()
+        }
+    });
+
+    if let Some(key_pair) = existing_key {
+        // The key decides the algorithm; --key-type may only pick its signature scheme
+        let detected = key::key_type_of(&key_pair)?;
+        opts.key_type = match explicit_key_type {
+            Some(key_type) if key_type.same_key_as(detected) => key_type,
+            Some(key_type) => bail!(
+                "--key-type {:?} does not match the {:?} key in --key",
+                key_type,
+                detected
+            ),
+            None => detected,
+        };
+        opts.existing_key = Some(cert::with_key_type(&key_pair, opts.key_type)?);
+    }
+
+    opts.pfx_format = pfx_format;
+    if key_usages.is_some() {
+        opts.key_usages = key_usages;
+    }
+    if extended_key_usages.is_some() {
+        opts.extended_key_usages = extended_key_usages;
+    }
+
+    if use_ca {
+        let issuer_dir = match &intermediate {
+            Some(name) => ca::intermediate_dir(&ca_dir, name),
+            None => ca_dir.clone(),
+        };
+        opts.issuer = Some(ca::load(&issuer_dir)?);
+    }
+
+    // The truststore trusts the root CA, or the certificate itself when self-signed
+    let trusted_root = if use_ca
+        && formats
+            .as_ref()
+            .is_some_and(|f| f.contains(&Format::Truststore))
+    {
+        let root_pem = fs::read_to_string(ca_dir.join(ca::CA_CERT_FILE))
+            .context("Failed to read root CA certificate")?;
+        Some(pem::parse(&root_pem)?.into_contents())
+    } else {
+        None
+    };
+
+    // Host names as they go into the SAN (the CN is one for server certificates)
+    let mut host_names = opts.sans.clone();
+    if opts.purpose == usage::Purpose::Server {
+        host_names.insert(0, opts.cn.clone());
+    }
+
+    // Unicode host names are written as punycode; show both forms afterwards
+    let mut idn_names = Vec::new();
+    for pair in san::idn_names(&host_names) {
+        if !idn_names.contains(&pair) {
+            idn_names.push(pair);
+        }
+    }
+
+    let mut warned = Vec::new();
+    for (name, suffix) in san::public_suffix_wildcards(&host_names) {
+        if !warned.contains(&name) {
+            eprintln!(
+                "{}",
+                Style::new().yellow().apply_to(
+                    t("wildcard_public_suffix")
+                        .replace("{name}", &name)
+                        .replace("{suffix}", &suffix)
+                )
+            );
+            warned.push(name);
+        }
+    }
 
     if !quiet {
-        println!("{}", t("generating"));
+        if csr_mode {
+            println!("{}", t("generating_csr"));
+        } else {
+            println!("{}", t("generating"));
+        }
     }
 
     if verbose {
@@ -60,15 +334,39 @@ fn main() -> Result<()> {
         println!("  SANs: {:?}", opts.sans);
         println!("  Days: {}", opts.validity_days);
         println!("  Key Type: {:?}", opts.key_type);
+        println!("  Purpose: {:?}", opts.purpose);
+        let key_usages = opts
+            .key_usages
+            .clone()
+            .unwrap_or_else(|| usage::default_key_usages(opts.purpose, opts.key_type));
+        let names: Vec<&str> = key_usages.iter().map(usage::key_usage_name).collect();
+        println!("  Key Usage: {}", names.join(", "));
+        let extended_key_usages = opts
+            .extended_key_usages
+            .clone()
+            .unwrap_or_else(|| usage::default_extended_key_usages(opts.purpose));
+        let names: Vec<String> = extended_key_usages
+            .iter()
+            .map(usage::extended_key_usage_name)
+            .collect();
+        println!("  Extended Key Usage: {}", names.join(", "));
+        if key_file.is_none() && opts.key_type.is_rsa() {
+            println!("  RSA Bits: {}", opts.rsa_bits);
+        }
+        if let Some(path) = &key_file {
+            println!("  Key File: {}", path.display());
+        }
         if opts.pfx_password.is_empty() {
             println!("  PFX Password: (none)");
         } else {
             println!("  PFX Password: (set)");
         }
+        match &opts.issuer {
+            Some(ca) => println!("  Issuer: {}", ca.common_name()),
+            None => println!("  Issuer: (self-signed)"),
+        }
     }
 
-    let generated = cert::generate_cert(opts).context("Failed to generate certificate")?;
-
     // Determine output directory
     let output_path = output_dir.unwrap_or_else(|| Path::new(".").to_path_buf());
 
@@ -77,60 +375,120 @@ fn main() -> Result<()> {
         fs::create_dir_all(&output_path).context("Failed to create output directory")?;
     }
 
-    let key_path = output_path.join(format!("{}.key", file_name));
-    let crt_path = output_path.join(format!("{}.crt", file_name));
-    let pem_path = output_path.join(format!("{}.pem", file_name));
-    let pfx_path = output_path.join(format!("{}.pfx", file_name));
-
-    // Check for existing files and prompt for overwrite
-    let paths = [&key_path, &crt_path, &pem_path, &pfx_path];
-    let existing_files: Vec<_> = paths.iter().filter(|p| p.exists()).collect();
-
-    if !existing_files.is_empty() && !yes {
-        println!(
-            "{}",
-            Style::new().yellow().apply_to(t("files_exist_warning"))
-        );
-        for file in &existing_files {
-            println!("  - {}", file.display());
+    // PEM and DER key as written (encrypted with --encrypt-key)
+    let key_out = |key_pem: &str, key_der: &[u8]| -> Result<(String, Vec<u8>)> {
+        match &key_encryption {
+            Some((passphrase, kdf)) => {
+                let encrypted = key::encrypt_pem(key_pem, passphrase, *kdf)?;
+                let der = pem::parse(&encrypted)?.into_contents();
+                Ok((encrypted, der))
+            }
+            None => Ok((key_pem.to_string(), key_der.to_vec())),
         }
-        print!("{} ", t("overwrite_prompt"));
-        io::stdout().flush()?;
+    };
+
+    // Contents of every file to write, in output order
+    let mut files: Vec<(Format, Vec<u8>)> = Vec::new();
+    // Issued certificate, recorded in the inventory once written
+    let mut cert_der = None;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = input.trim().to_lowercase();
+    if csr_mode {
+        let generated = cert::generate_csr(opts).context("Failed to generate CSR")?;
+        let (key_pem, key_der) = key_out(&generated.key_pem, &generated.key_der)?;
 
-        if input != "y" && input != "yes" {
-            println!("{}", t("aborted"));
-            return Ok(());
+        for format in formats.unwrap_or_else(|| output::default_formats(true, false)) {
+            let contents = match format {
+                Format::Key => key_pem.clone().into_bytes(),
+                Format::DerKey => key_der.clone(),
+                Format::Csr => generated.csr_pem.clone().into_bytes(),
+                _ => unreachable!("checked against CSR mode above"),
+            };
+            files.push((format, contents));
         }
+    } else {
+        let generated = cert::generate_cert(opts).context("Failed to generate certificate")?;
+        let has_chain = !generated.chain_pem.is_empty();
+        let chain_der = generated
+            .chain_pem
+            .iter()
+            .map(|c| Ok(pem::parse(c)?.into_contents()))
+            .collect::<Result<Vec<_>>>()?;
+        let (key_pem, key_der) = key_out(&generated.key_pem, &generated.key_der)?;
+
+        for format in formats.unwrap_or_else(|| output::default_formats(false, has_chain)) {
+            let contents = match format {
+                Format::Key => key_pem.clone().into_bytes(),
+                Format::Crt => generated.cert_pem.clone().into_bytes(),
+                // server.pem = key + cert bundle
+                Format::Pem => format!("{}\n{}", key_pem, generated.cert_pem).into_bytes(),
+                Format::Pfx => generated.pfx.clone(),
+                Format::Der => generated.cert_der.clone(),
+                Format::DerKey => key_der.clone(),
+                // server-fullchain.pem = cert + intermediates
+                Format::Fullchain => {
+                    format!("{}{}", generated.cert_pem, generated.chain_pem.concat()).into_bytes()
+                }
+                // server.jks = key entry with the certificate and intermediates
+                Format::Jks => {
+                    let mut chain: Vec<&[u8]> = vec![&generated.cert_der];
+                    chain.extend(chain_der.iter().map(|c| c.as_slice()));
+                    jks::keystore(&jks_alias, &generated.key_der, &chain, &jks_password)?
+                }
+                Format::Truststore => match &trusted_root {
+                    Some(root) => jks::truststore(&[("ca", root)], &jks_password)?,
+                    None => jks::truststore(&[(&jks_alias, &generated.cert_der)], &jks_password)?,
+                },
+                Format::Csr => unreachable!("csr selects CSR mode"),
+            };
+            files.push((format, contents));
+        }
+        cert_der = Some(generated.cert_der);
     }
 
-    // Write files
-    fs::write(&key_path, &generated.key_pem).context("Failed to write key file")?;
-    fs::write(&crt_path, &generated.cert_pem).context("Failed to write crt file")?;
+    // An existing --key is left where it is
+    if key_file.is_some() {
+        files.retain(|(format, _)| *format != Format::Key);
+    }
 
-    // server.pem = key + cert bundle
-    let bundle = format!("{}\n{}", generated.key_pem, generated.cert_pem);
-    fs::write(&pem_path, bundle).context("Failed to write pem file")?;
+    let paths: Vec<PathBuf> = files
+        .iter()
+        .map(|(format, _)| output_path.join(format.file_name(&file_name)))
+        .collect();
+    let path_refs: Vec<&PathBuf> = paths.iter().collect();
 
-    fs::write(&pfx_path, &generated.pfx).context("Failed to write pfx file")?;
+    // Check for existing files and prompt for overwrite
+    if !output::confirm_overwrite(&path_refs, yes)? {
+        return Ok(());
+    }
+
+    for ((format, contents), path) in files.iter().zip(&paths) {
+        fs::write(path, contents)
+            .with_context(|| format!("Failed to write {} file", format.name()))?;
+    }
+
+    // Record the file a user would look for first
+    let cert_path = [
+        Format::Crt,
+        Format::Pem,
+        Format::Fullchain,
+        Format::Der,
+        Format::Pfx,
+        Format::Jks,
+    ]
+    .iter()
+    .find_map(|f| files.iter().position(|(format, _)| format == f));
+    if let (Some(der), Some(i)) = (&cert_der, cert_path) {
+        inventory::record(&inventory_path, &file_name, &paths[i], der);
+    }
 
     if !quiet {
-        let success_style = Style::new().green().bold();
-        let path_style = Style::new().cyan();
-
-        println!("{}", success_style.apply_to(t("success")));
-        println!(
-            "{} {}",
-            t("saved_to"),
-            path_style.apply_to(fs::canonicalize(&output_path)?.display())
-        );
-        println!("  - {}", path_style.apply_to(key_path.display()));
-        println!("  - {}", path_style.apply_to(crt_path.display()));
-        println!("  - {}", path_style.apply_to(pem_path.display()));
-        println!("  - {}", path_style.apply_to(pfx_path.display()));
+        output::print_saved(&output_path, &path_refs)?;
+        if !idn_names.is_empty() {
+            println!("{}", t("idn_names"));
+            for (unicode, ascii) in &idn_names {
+                println!("  - {} ({})", unicode, ascii);
+            }
+        }
     }
 
     Ok(())
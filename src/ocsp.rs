@@ -0,0 +1,811 @@
+use crate::ca::{self, LocalCa, INTERMEDIATES_DIR};
+use crate::cert::{self, KeyType};
+use crate::cli::OcspServeArgs;
+use crate::crl::{self, RevocationDb};
+use crate::i18n::t;
+use crate::key;
+use crate::pss;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use console::Style;
+use der::asn1::{BitString, GeneralizedTime, Int, Null, ObjectIdentifier, OctetString};
+use der::{Any, Choice, Decode, Encode, Enumerated, Sequence};
+use rcgen::{
+    CertificateParams, CustomExtension, DnType, ExtendedKeyUsagePurpose, KeyPair, KeyUsagePurpose,
+};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use time::format_description::well_known::Rfc3339;
+use time::{Duration, OffsetDateTime};
+use x509_cert::ext::pkix::crl::CrlReason;
+use x509_cert::ext::Extension;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+
+// OCSP responder (RFC 6960) answering from the local CA's revocation database
+
+/// id-pkix-ocsp-basic
+const ID_PKIX_OCSP_BASIC: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.48.1.1");
+
+/// id-pkix-ocsp-nonce, echoed back when present in the request
+const ID_PKIX_OCSP_NONCE: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.48.1.2");
+
+/// id-pkix-ocsp-nocheck: clients need not check the delegated responder's own status
+const ID_PKIX_OCSP_NOCHECK: [u64; 10] = [1, 3, 6, 1, 5, 5, 7, 48, 1, 5];
+
+/// id-ad-ocsp, the access method of the OCSP URL in Authority Information Access
+pub const ID_AD_OCSP: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.5.5.7.48.1");
+
+const ID_SHA1: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.14.3.2.26");
+const ID_SHA256: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.1");
+const ID_SHA384: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.2");
+const ID_SHA512: ObjectIdentifier = ObjectIdentifier::new_unwrap("2.16.840.1.101.3.4.2.3");
+
+/// File name of the delegated responder key inside a CA directory
+pub const RESPONDER_KEY_FILE: &str = "ocsp.key";
+
+/// File name of the delegated responder certificate inside a CA directory
+pub const RESPONDER_CERT_FILE: &str = "ocsp.crt";
+
+/// Validity of delegated responder certificates; they are re-issued when this runs out
+const RESPONDER_DAYS: i64 = 30;
+
+/// Largest request line plus headers accepted from a client
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+
+/// Largest POST body accepted; real OCSP requests are a few hundred bytes
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// How long a client may stall before its connection is dropped; requests
+/// are served one at a time, so this bounds how long others wait
+const CLIENT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[derive(Sequence)]
+struct OcspRequest {
+    tbs_request: TbsRequest,
+    #[asn1(context_specific = "0", optional = "true")]
+    optional_signature: Option<Any>,
+}
+
+#[derive(Sequence)]
+struct TbsRequest {
+    #[asn1(context_specific = "0", optional = "true")]
+    version: Option<u8>,
+    #[asn1(context_specific = "1", optional = "true")]
+    requestor_name: Option<Any>,
+    request_list: Vec<Request>,
+    #[asn1(context_specific = "2", optional = "true")]
+    request_extensions: Option<Vec<Extension>>,
+}
+
+#[derive(Sequence)]
+struct Request {
+    req_cert: CertId,
+    #[asn1(context_specific = "0", optional = "true")]
+    single_request_extensions: Option<Vec<Extension>>,
+}
+
+#[derive(Sequence, Clone)]
+struct CertId {
+    hash_algorithm: AlgorithmIdentifierOwned,
+    issuer_name_hash: OctetString,
+    issuer_key_hash: OctetString,
+    serial_number: Int,
+}
+
+#[derive(Sequence)]
+struct OcspResponse {
+    response_status: ResponseStatus,
+    #[asn1(context_specific = "0", optional = "true")]
+    response_bytes: Option<ResponseBytes>,
+}
+
+#[derive(Enumerated, Copy, Clone, Debug, PartialEq)]
+#[repr(u32)]
+enum ResponseStatus {
+    Successful = 0,
+    MalformedRequest = 1,
+    InternalError = 2,
+    Unauthorized = 6,
+}
+
+#[derive(Sequence)]
+struct ResponseBytes {
+    response_type: ObjectIdentifier,
+    response: OctetString,
+}
+
+#[derive(Sequence)]
+struct BasicOcspResponse {
+    tbs_response_data: ResponseData,
+    signature_algorithm: AlgorithmIdentifierOwned,
+    signature: BitString,
+    #[asn1(context_specific = "0", optional = "true")]
+    certs: Option<Vec<x509_cert::Certificate>>,
+}
+
+#[derive(Sequence)]
+struct ResponseData {
+    /// ResponderID, always byKey (SHA-1 of the signer's public key)
+    #[asn1(context_specific = "2")]
+    responder_key_hash: OctetString,
+    produced_at: GeneralizedTime,
+    responses: Vec<SingleResponse>,
+    #[asn1(context_specific = "1", optional = "true")]
+    response_extensions: Option<Vec<Extension>>,
+}
+
+#[derive(Sequence)]
+struct SingleResponse {
+    cert_id: CertId,
+    cert_status: CertStatus,
+    this_update: GeneralizedTime,
+    #[asn1(context_specific = "0", optional = "true")]
+    next_update: Option<GeneralizedTime>,
+}
+
+#[derive(Choice)]
+enum CertStatus {
+    #[asn1(context_specific = "0", tag_mode = "IMPLICIT")]
+    Good(Null),
+    #[asn1(context_specific = "1", tag_mode = "IMPLICIT", constructed = "true")]
+    Revoked(RevokedInfo),
+    #[asn1(context_specific = "2", tag_mode = "IMPLICIT")]
+    Unknown(Null),
+}
+
+#[derive(Sequence)]
+struct RevokedInfo {
+    revocation_time: GeneralizedTime,
+    #[asn1(context_specific = "0", optional = "true")]
+    revocation_reason: Option<CrlReason>,
+}
+
+impl CertStatus {
+    fn name(&self) -> &'static str {
+        match self {
+            CertStatus::Good(_) => "good",
+            CertStatus::Revoked(_) => "revoked",
+            CertStatus::Unknown(_) => "unknown",
+        }
+    }
+}
+
+/// Key that signs responses for one CA: the CA key, or a delegated responder
+/// key with its certificate
+struct Signer {
+    key_pair: KeyPair,
+    key_type: KeyType,
+    key_hash: Vec<u8>,
+    cert: Option<x509_cert::Certificate>,
+}
+
+/// A CA whose certificates this responder answers for
+struct ServedCa {
+    dir: PathBuf,
+    name: String,
+    subject_der: Vec<u8>,
+    public_key: Vec<u8>,
+    signer: Signer,
+}
+
+pub struct Responder {
+    cas: Vec<ServedCa>,
+    next_update_days: i64,
+}
+
+/// An encoded response and the status of each certificate asked about
+pub struct Answer {
+    pub der: Vec<u8>,
+    pub statuses: Vec<(String, &'static str)>,
+}
+
+impl Responder {
+    /// Load the root CA and its intermediates from the CA home directory
+    pub fn load(ca_dir: &Path, delegated: bool, next_update_days: i64) -> Result<Self> {
+        let mut dirs = vec![ca_dir.to_path_buf()];
+        if let Ok(entries) = fs::read_dir(ca_dir.join(INTERMEDIATES_DIR)) {
+            // Stray files (.DS_Store, notes) are not CAs
+            let mut intermediates: Vec<PathBuf> = entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.path())
+                .filter(|p| p.is_dir())
+                .collect();
+            intermediates.sort();
+            dirs.extend(intermediates);
+        }
+
+        let mut cas = Vec::new();
+        for dir in dirs {
+            // A broken intermediate should not take down the root and the others
+            let ca = match ca::load(&dir) {
+                Ok(ca) => ca,
+                Err(e) if dir != ca_dir => {
+                    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+                    eprintln!(
+                        "{}",
+                        Style::new().yellow().apply_to(
+                            t("ocsp_intermediate_skipped")
+                                .replace("{name}", &name)
+                                .replace("{error}", &format!("{:#}", e))
+                        )
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+            let der = pem::parse(&ca.cert_pem)?.into_contents();
+            let (_, x509) = x509_parser::parse_x509_certificate(&der)
+                .map_err(|e| anyhow::anyhow!("Failed to parse CA certificate: {}", e))?;
+            let subject_der = x509.subject().as_raw().to_vec();
+            let public_key = x509.public_key().subject_public_key.data.to_vec();
+
+            let name = ca.common_name();
+            let signer = if delegated {
+                let (key_pair, cert_der) = delegated_responder(&dir, &ca)?;
+                let cert = x509_cert::Certificate::from_der(&cert_der)
+                    .context("Failed to parse OCSP responder certificate")?;
+                let key_hash = Sha1::digest(
+                    cert.tbs_certificate
+                        .subject_public_key_info
+                        .subject_public_key
+                        .raw_bytes(),
+                )
+                .to_vec();
+                Signer {
+                    key_type: key::key_type_of(&key_pair)?,
+                    key_pair,
+                    key_hash,
+                    cert: Some(cert),
+                }
+            } else {
+                Signer {
+                    key_pair: ca.key_pair,
+                    key_type: ca.key_type,
+                    key_hash: Sha1::digest(&public_key).to_vec(),
+                    cert: None,
+                }
+            };
+
+            cas.push(ServedCa {
+                dir,
+                name,
+                subject_der,
+                public_key,
+                signer,
+            });
+        }
+
+        Ok(Responder {
+            cas,
+            next_update_days,
+        })
+    }
+
+    /// Answer a DER-encoded OCSP request
+    pub fn respond(&self, request_der: &[u8]) -> Answer {
+        match self.try_respond(request_der) {
+            Ok(answer) => answer,
+            Err(status) => Answer {
+                der: OcspResponse {
+                    response_status: status,
+                    response_bytes: None,
+                }
+                .to_der()
+                .unwrap_or_default(),
+                statuses: Vec::new(),
+            },
+        }
+    }
+
+    fn try_respond(&self, request_der: &[u8]) -> Result<Answer, ResponseStatus> {
+        let request =
+            OcspRequest::from_der(request_der).map_err(|_| ResponseStatus::MalformedRequest)?;
+        let requests = &request.tbs_request.request_list;
+        let Some(first) = requests.first() else {
+            return Err(ResponseStatus::MalformedRequest);
+        };
+
+        // One signer per response: the CA of the first certificate asked about
+        let served = self
+            .cas
+            .iter()
+            .find(|ca| ca.issued(&first.req_cert))
+            .ok_or(ResponseStatus::Unauthorized)?;
+        let db = RevocationDb::load(&served.dir).map_err(|_| ResponseStatus::InternalError)?;
+
+        let now = OffsetDateTime::now_utc();
+        let this_update = generalized_time(now)?;
+        let next_update = generalized_time(now + Duration::days(self.next_update_days))?;
+
+        let mut statuses = Vec::new();
+        let mut responses = Vec::new();
+        for req in requests {
+            let cert_id = req.req_cert.clone();
+            let serial = crl::serial_of(cert_id.serial_number.as_bytes());
+            let cert_status = if !served.issued(&cert_id) {
+                CertStatus::Unknown(Null)
+            } else {
+                match db.find(&serial) {
+                    Some(entry) => CertStatus::Revoked(RevokedInfo {
+                        revocation_time: OffsetDateTime::parse(&entry.revoked_at, &Rfc3339)
+                            .map_err(|_| ResponseStatus::InternalError)
+                            .and_then(generalized_time)?,
+                        revocation_reason: crl::parse_reason(&entry.reason)
+                            .ok()
+                            .and_then(|r| CrlReason::try_from(r as u32).ok()),
+                    }),
+                    // Anything this CA did not revoke is good (RFC 6960 §2.2)
+                    None => CertStatus::Good(Null),
+                }
+            };
+            statuses.push((serial, cert_status.name()));
+            responses.push(SingleResponse {
+                cert_id,
+                cert_status,
+                this_update,
+                next_update: Some(next_update),
+            });
+        }
+
+        let nonce = request
+            .tbs_request
+            .request_extensions
+            .iter()
+            .flatten()
+            .find(|ext| ext.extn_id == ID_PKIX_OCSP_NONCE)
+            .cloned();
+
+        let tbs_response_data = ResponseData {
+            responder_key_hash: OctetString::new(served.signer.key_hash.clone())
+                .map_err(|_| ResponseStatus::InternalError)?,
+            produced_at: this_update,
+            responses,
+            response_extensions: nonce.map(|ext| vec![ext]),
+        };
+        let tbs = tbs_response_data
+            .to_der()
+            .map_err(|_| ResponseStatus::InternalError)?;
+        let (signature_algorithm, signature) =
+            sign(&served.signer.key_pair, served.signer.key_type, &tbs)
+                .map_err(|_| ResponseStatus::InternalError)?;
+
+        let basic = BasicOcspResponse {
+            tbs_response_data,
+            signature_algorithm,
+            signature: BitString::from_bytes(&signature)
+                .map_err(|_| ResponseStatus::InternalError)?,
+            certs: served.signer.cert.clone().map(|c| vec![c]),
+        };
+        let der = OcspResponse {
+            response_status: ResponseStatus::Successful,
+            response_bytes: Some(ResponseBytes {
+                response_type: ID_PKIX_OCSP_BASIC,
+                response: basic
+                    .to_der()
+                    .and_then(OctetString::new)
+                    .map_err(|_| ResponseStatus::InternalError)?,
+            }),
+        }
+        .to_der()
+        .map_err(|_| ResponseStatus::InternalError)?;
+
+        Ok(Answer { der, statuses })
+    }
+}
+
+impl ServedCa {
+    /// Whether a CertID names this CA as the issuer
+    fn issued(&self, cert_id: &CertId) -> bool {
+        let oid = cert_id.hash_algorithm.oid;
+        match (
+            digest(oid, &self.subject_der),
+            digest(oid, &self.public_key),
+        ) {
+            (Some(name_hash), Some(key_hash)) => {
+                cert_id.issuer_name_hash.as_bytes() == name_hash.as_slice()
+                    && cert_id.issuer_key_hash.as_bytes() == key_hash.as_slice()
+            }
+            _ => false,
+        }
+    }
+}
+
+fn digest(oid: ObjectIdentifier, data: &[u8]) -> Option<Vec<u8>> {
+    match oid {
+        ID_SHA1 => Some(Sha1::digest(data).to_vec()),
+        ID_SHA256 => Some(Sha256::digest(data).to_vec()),
+        ID_SHA384 => Some(Sha384::digest(data).to_vec()),
+        ID_SHA512 => Some(Sha512::digest(data).to_vec()),
+        _ => None,
+    }
+}
+
+fn generalized_time(time: OffsetDateTime) -> Result<GeneralizedTime, ResponseStatus> {
+    let secs = u64::try_from(time.unix_timestamp()).map_err(|_| ResponseStatus::InternalError)?;
+    GeneralizedTime::from_unix_duration(std::time::Duration::from_secs(secs))
+        .map_err(|_| ResponseStatus::InternalError)
+}
+
+/// Sign in the same scheme as the signer's certificates and CRLs
+fn sign(
+    key_pair: &KeyPair,
+    key_type: KeyType,
+    message: &[u8],
+) -> Result<(AlgorithmIdentifierOwned, Vec<u8>)> {
+    use ring::signature::{self, EcdsaKeyPair, Ed25519KeyPair, RsaKeyPair};
+
+    let rng = ring::rand::SystemRandom::new();
+    let pkcs8 = key_pair.serialize_der();
+    let rejected = |e: ring::error::KeyRejected| anyhow::anyhow!("Unusable signing key: {}", e);
+    let failed = |_| anyhow::anyhow!("Failed to sign OCSP response");
+
+    if pss::is_pss(key_type) {
+        let alg = match key_type {
+            KeyType::RsaPssSha384 => &signature::RSA_PSS_SHA384,
+            KeyType::RsaPssSha512 => &signature::RSA_PSS_SHA512,
+            _ => &signature::RSA_PSS_SHA256,
+        };
+        let signer = RsaKeyPair::from_pkcs8(&pkcs8).map_err(rejected)?;
+        let mut sig = vec![0u8; signer.public().modulus_len()];
+        signer.sign(alg, &rng, message, &mut sig).map_err(failed)?;
+        return Ok((pss::algorithm(key_type)?, sig));
+    }
+
+    let (oid, signature) = match key_type {
+        KeyType::Ecdsa | KeyType::EcdsaP384 => {
+            let (alg, oid) = if key_type == KeyType::Ecdsa {
+                (
+                    &signature::ECDSA_P256_SHA256_ASN1_SIGNING,
+                    "1.2.840.10045.4.3.2",
+                )
+            } else {
+                (
+                    &signature::ECDSA_P384_SHA384_ASN1_SIGNING,
+                    "1.2.840.10045.4.3.3",
+                )
+            };
+            let signer = EcdsaKeyPair::from_pkcs8(alg, &pkcs8, &rng).map_err(rejected)?;
+            let sig = signer.sign(&rng, message).map_err(failed)?;
+            (oid, sig.as_ref().to_vec())
+        }
+        KeyType::Ed25519 => {
+            let signer = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&pkcs8).map_err(rejected)?;
+            ("1.3.101.112", signer.sign(message).as_ref().to_vec())
+        }
+        _ => {
+            let (alg, oid) = match key_type {
+                KeyType::RsaSha384 => (&signature::RSA_PKCS1_SHA384, "1.2.840.113549.1.1.12"),
+                KeyType::RsaSha512 => (&signature::RSA_PKCS1_SHA512, "1.2.840.113549.1.1.13"),
+                _ => (&signature::RSA_PKCS1_SHA256, "1.2.840.113549.1.1.11"),
+            };
+            let signer = RsaKeyPair::from_pkcs8(&pkcs8).map_err(rejected)?;
+            let mut sig = vec![0u8; signer.public().modulus_len()];
+            signer.sign(alg, &rng, message, &mut sig).map_err(failed)?;
+            (oid, sig)
+        }
+    };
+
+    // RSA signature algorithms carry NULL parameters; ECDSA and Ed25519 none
+    let parameters = if key_type.is_rsa() {
+        Some(Any::from(Null))
+    } else {
+        None
+    };
+    Ok((
+        AlgorithmIdentifierOwned {
+            oid: ObjectIdentifier::new_unwrap(oid),
+            parameters,
+        },
+        signature,
+    ))
+}
+
+/// Load the delegated responder of a CA, issuing a new one when it is missing,
+/// expiring within a day, or was issued by a different CA key
+fn delegated_responder(dir: &Path, ca: &LocalCa) -> Result<(KeyPair, Vec<u8>)> {
+    let key_path = dir.join(RESPONDER_KEY_FILE);
+    let cert_path = dir.join(RESPONDER_CERT_FILE);
+
+    if let (Ok(key_pem), Ok(cert_pem)) = (
+        fs::read_to_string(&key_path),
+        fs::read_to_string(&cert_path),
+    ) {
+        let der = pem::parse(&cert_pem)?.into_contents();
+        let (_, x509) = x509_parser::parse_x509_certificate(&der)
+            .map_err(|e| anyhow::anyhow!("Failed to parse OCSP responder certificate: {}", e))?;
+        let expires = x509.validity().not_after.to_datetime();
+        let same_ca = x509.extensions().iter().any(|ext| {
+            matches!(
+                ext.parsed_extension(),
+                x509_parser::extensions::ParsedExtension::AuthorityKeyIdentifier(aki)
+                    if aki.key_identifier.as_ref().map(|k| k.0)
+                        == Some(ca.cert.key_identifier().as_slice())
+            )
+        });
+        if same_ca && expires > OffsetDateTime::now_utc() + Duration::days(1) {
+            let key_pair = KeyPair::from_pem(&key_pem).context("Failed to parse OCSP key")?;
+            return Ok((key_pair, der));
+        }
+    }
+
+    let key_pair = cert::generate_key_pair(KeyType::Ecdsa, cert::DEFAULT_RSA_BITS)?;
+    let mut params = CertificateParams::default();
+    params.distinguished_name.push(
+        DnType::CommonName,
+        format!("{} OCSP Responder", ca.common_name()),
+    );
+    params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+    params.extended_key_usages = vec![ExtendedKeyUsagePurpose::OcspSigning];
+    params.custom_extensions = vec![CustomExtension::from_oid_content(
+        &ID_PKIX_OCSP_NOCHECK,
+        Null.to_der()?,
+    )];
+    params.use_authority_key_identifier_extension = true;
+    params.serial_number = Some(cert::random_serial()?);
+    let now = OffsetDateTime::now_utc();
+    params.not_before = now;
+    params.not_after = now + Duration::days(RESPONDER_DAYS);
+
+    let cert = params.signed_by(&key_pair, &ca.cert, &ca.key_pair)?;
+    let der = ca.resign(cert.der())?;
+    ca::write_private(&key_path, &key_pair.serialize_pem())
+        .context("Failed to write OCSP responder key")?;
+    fs::write(&cert_path, cert::to_pem("CERTIFICATE", &der))
+        .context("Failed to write OCSP responder certificate")?;
+    Ok((key_pair, der))
+}
+
+/// Handle `suresign ocsp-serve`
+pub fn run(args: OcspServeArgs, ca_dir: &Path, quiet: bool) -> Result<()> {
+    if args.next_update < 1 {
+        bail!("--next-update must be at least 1 day");
+    }
+    let responder = Responder::load(ca_dir, args.delegated, args.next_update)?;
+    // GET requests are `<path>/<base64 request>`
+    let prefix = format!("/{}/", args.path.trim_matches('/')).replace("//", "/");
+    let listener = TcpListener::bind(&args.listen)
+        .with_context(|| format!("Failed to listen on {}", args.listen))?;
+
+    println!(
+        "{}",
+        t("ocsp_listening").replace("{addr}", &listener.local_addr()?.to_string())
+    );
+    for ca in &responder.cas {
+        let signer = if ca.signer.cert.is_some() {
+            ca.dir.join(RESPONDER_CERT_FILE).display().to_string()
+        } else {
+            t("ocsp_ca_signed")
+        };
+        println!("  - {} ({})", ca.name, signer);
+    }
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        match handle(&responder, &prefix, stream) {
+            Ok(statuses) if !quiet => {
+                for (serial, status) in statuses {
+                    println!("  {} {}", serial, status);
+                }
+            }
+            Ok(_) => {}
+            Err(e) => eprintln!("{}", e),
+        }
+    }
+    Ok(())
+}
+
+/// Serve one HTTP request: POST with a DER body, or GET with the base64
+/// request in the path (RFC 6960 Appendix A)
+fn handle(
+    responder: &Responder,
+    prefix: &str,
+    mut stream: TcpStream,
+) -> Result<Vec<(String, &'static str)>> {
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut reader = BufReader::new((&stream).take((MAX_HEADER_BYTES + MAX_BODY_BYTES) as u64));
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut content_length = 0;
+    let mut header_bytes = request_line.len();
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 || header.trim().is_empty() {
+            break;
+        }
+        header_bytes += header.len();
+        if header_bytes > MAX_HEADER_BYTES {
+            return reply(&mut stream, "431 Request Header Fields Too Large");
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                content_length = value.trim().parse().unwrap_or(0);
+            }
+        }
+    }
+
+    let request = match method.as_str() {
+        "POST" if content_length > MAX_BODY_BYTES => {
+            return reply(&mut stream, "413 Payload Too Large");
+        }
+        "POST" => {
+            let mut body = vec![0u8; content_length];
+            reader.read_exact(&mut body)?;
+            Some(body)
+        }
+        "GET" => get_request(&path, prefix),
+        _ => None,
+    };
+
+    let Some(request) = request else {
+        return reply(&mut stream, "400 Bad Request");
+    };
+
+    let answer = responder.respond(&request);
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: application/ocsp-response\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        answer.der.len()
+    )?;
+    stream.write_all(&answer.der)?;
+    Ok(answer.statuses)
+}
+
+/// Send an empty error response
+fn reply(stream: &mut TcpStream, status: &str) -> Result<Vec<(String, &'static str)>> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+        status
+    )?;
+    Ok(Vec::new())
+}
+
+/// DER request of a GET path: everything after the prefix, percent-decoded
+/// and base64-decoded (base64 itself may contain `/`)
+fn get_request(path: &str, prefix: &str) -> Option<Vec<u8>> {
+    let encoded = percent_decode(path.strip_prefix(prefix)?);
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .ok()
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| s.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ca::CaOptions;
+    use crate::crl::RevokedEntry;
+
+    fn cert_id(ca: &LocalCa, serial: &[u8]) -> CertId {
+        let der = pem::parse(&ca.cert_pem).unwrap().into_contents();
+        let (_, x509) = x509_parser::parse_x509_certificate(&der).unwrap();
+        CertId {
+            hash_algorithm: AlgorithmIdentifierOwned {
+                oid: ID_SHA1,
+                parameters: Some(Any::from(Null)),
+            },
+            issuer_name_hash: OctetString::new(Sha1::digest(x509.subject().as_raw()).to_vec())
+                .unwrap(),
+            issuer_key_hash: OctetString::new(
+                Sha1::digest(&x509.public_key().subject_public_key.data).to_vec(),
+            )
+            .unwrap(),
+            serial_number: Int::new(serial).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_responds_from_revocation_db() {
+        let dir = std::env::temp_dir().join(format!("suresign-ocsp-{}", std::process::id()));
+        let ca = ca::create_root(CaOptions {
+            cn: "OCSP Test CA".to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type: KeyType::Ecdsa,
+        })
+        .unwrap();
+        ca::save(&dir, &ca).unwrap();
+        RevocationDb {
+            crl_number: 0,
+            revoked: vec![RevokedEntry {
+                serial: "4a1b".to_string(),
+                subject: None,
+                reason: "key-compromise".to_string(),
+                revoked_at: "2026-01-02T03:04:05Z".to_string(),
+            }],
+        }
+        .save(&dir)
+        .unwrap();
+        // A stray file and a broken intermediate are skipped
+        let intermediates = dir.join(INTERMEDIATES_DIR);
+        fs::create_dir_all(intermediates.join("broken")).unwrap();
+        fs::write(intermediates.join(".DS_Store"), b"junk").unwrap();
+
+        let responder = Responder::load(&dir, true, 1).unwrap();
+        assert_eq!(responder.cas.len(), 1);
+        let request = OcspRequest {
+            tbs_request: TbsRequest {
+                version: None,
+                requestor_name: None,
+                request_list: vec![
+                    Request {
+                        req_cert: cert_id(&ca, &[0x4a, 0x1b]),
+                        single_request_extensions: None,
+                    },
+                    Request {
+                        req_cert: cert_id(&ca, &[0x01]),
+                        single_request_extensions: None,
+                    },
+                ],
+                request_extensions: None,
+            },
+            optional_signature: None,
+        };
+        let answer = responder.respond(&request.to_der().unwrap());
+        assert_eq!(
+            answer.statuses,
+            vec![("4a1b".to_string(), "revoked"), ("01".to_string(), "good")]
+        );
+
+        let response = OcspResponse::from_der(&answer.der).unwrap();
+        assert_eq!(response.response_status, ResponseStatus::Successful);
+        let bytes = response.response_bytes.unwrap();
+        let basic = BasicOcspResponse::from_der(bytes.response.as_bytes()).unwrap();
+        assert_eq!(basic.certs.map(|c| c.len()), Some(1));
+        assert!(dir.join(RESPONDER_CERT_FILE).exists());
+
+        let malformed = OcspResponse::from_der(&responder.respond(b"junk").der).unwrap();
+        assert_eq!(malformed.response_status, ResponseStatus::MalformedRequest);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_get_request_keeps_slashes_in_base64() {
+        let der = [0xfb, 0xff, 0xbf, 0x30];
+        let encoded = base64::engine::general_purpose::STANDARD.encode(der);
+        assert!(encoded.contains('/'));
+
+        assert_eq!(get_request(&format!("/{}", encoded), "/").unwrap(), der);
+        let escaped = encoded.replace('/', "%2F").replace('+', "%2B");
+        assert_eq!(
+            get_request(&format!("/ocsp/{}", escaped), "/ocsp/").unwrap(),
+            der
+        );
+        assert!(get_request(&format!("/other/{}", encoded), "/ocsp/").is_none());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::i18n::t;
+use anyhow::{bail, Result};
+use console::Style;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// An output file that can be selected with `--formats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// `<name>.key`: PEM private key
+    Key,
+    /// `<name>.crt`: PEM certificate
+    Crt,
+    /// `<name>.pem`: key + certificate bundle
+    Pem,
+    /// `<name>.pfx`: PKCS#12 with key, certificate and chain
+    Pfx,
+    /// `<name>.cer`: DER certificate
+    Der,
+    /// `<name>-key.der`: PKCS#8 DER private key
+    DerKey,
+    /// `<name>-fullchain.pem`: certificate + intermediates
+    Fullchain,
+    /// `<name>.csr`: certificate signing request (selects CSR mode)
+    Csr,
+    /// `<name>.jks`: Java KeyStore with the key and certificate chain
+    Jks,
+    /// `<name>-truststore.jks`: Java truststore with the trusted root
+    Truststore,
+}
+
+impl Format {
+    const ALL: [Format; 10] = [
+        Format::Key,
+        Format::Crt,
+        Format::Pem,
+        Format::Pfx,
+        Format::Der,
+        Format::DerKey,
+        Format::Fullchain,
+        Format::Csr,
+        Format::Jks,
+        Format::Truststore,
+    ];
+
+    /// Name used in `--formats` and the config file
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Key => "key",
+            Format::Crt => "crt",
+            Format::Pem => "pem",
+            Format::Pfx => "pfx",
+            Format::Der => "der",
+            Format::DerKey => "der-key",
+            Format::Fullchain => "fullchain",
+            Format::Csr => "csr",
+            Format::Jks => "jks",
+            Format::Truststore => "truststore",
+        }
+    }
+
+    /// File name written for this format
+    pub fn file_name(self, name: &str) -> String {
+        match self {
+            Format::Key => format!("{}.key", name),
+            Format::Crt => format!("{}.crt", name),
+            Format::Pem => format!("{}.pem", name),
+            Format::Pfx => format!("{}.pfx", name),
+            Format::Der => format!("{}.cer", name),
+            Format::DerKey => format!("{}-key.der", name),
+            Format::Fullchain => format!("{}-fullchain.pem", name),
+            Format::Csr => format!("{}.csr", name),
+            Format::Jks => format!("{}.jks", name),
+            Format::Truststore => format!("{}-truststore.jks", name),
+        }
+    }
+
+    /// Whether the format can be written in CSR mode
+    pub fn in_csr_mode(self) -> bool {
+        matches!(self, Format::Key | Format::DerKey | Format::Csr)
+    }
+}
+
+/// Parse a `--formats` list such as `key,crt,pfx`
+pub fn parse_formats(names: &[String]) -> Result<Vec<Format>> {
+    let mut formats = Vec::new();
+    for name in names {
+        let name = name.trim().to_lowercase();
+        let Some(format) = Format::ALL.iter().find(|f| f.name() == name) else {
+            let valid: Vec<&str> = Format::ALL.iter().map(|f| f.name()).collect();
+            bail!(
+                "Unknown output format '{}' (expected {})",
+                name,
+                valid.join(", ")
+            );
+        };
+        if !formats.contains(format) {
+            formats.push(*format);
+        }
+    }
+    if formats.is_empty() {
+        bail!("--formats must name at least one output format");
+    }
+    Ok(formats)
+}
+
+/// Formats written when none are selected
+pub fn default_formats(csr_mode: bool, has_chain: bool) -> Vec<Format> {
+    if csr_mode {
+        return vec![Format::Key, Format::Csr];
+    }
+    let mut formats = vec![Format::Key, Format::Crt, Format::Pem, Format::Pfx];
+    if has_chain {
+        formats.push(Format::Fullchain);
+    }
+    formats
+}
+
+/// Warn about files that already exist and ask before overwriting them.
+/// Returns false if the user declined.
+pub fn confirm_overwrite(paths: &[&PathBuf], yes: bool) -> Result<bool> {
+    let existing_files: Vec<_> = paths.iter().filter(|p| p.exists()).collect();
+
+    if !existing_files.is_empty() && !yes {
+        println!(
+            "{}",
+            Style::new().yellow().apply_to(t("files_exist_warning"))
+        );
+        for file in &existing_files {
+            println!("  - {}", file.display());
+        }
+        print!("{} ", t("overwrite_prompt"));
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+
+        if input != "y" && input != "yes" {
+            println!("{}", t("aborted"));
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Print the success message and the list of written files
+pub fn print_saved(output_path: &Path, paths: &[&PathBuf]) -> Result<()> {
+    let success_style = Style::new().green().bold();
+    let path_style = Style::new().cyan();
+
+    println!("{}", success_style.apply_to(t("success")));
+    println!(
+        "{} {}",
+        t("saved_to"),
+        path_style.apply_to(fs::canonicalize(output_path)?.display())
+    );
+    for path in paths {
+        println!("  - {}", path_style.apply_to(path.display()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_formats() {
+        let names: Vec<String> = ["key", " CRT", "der-key", "key"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            parse_formats(&names).unwrap(),
+            vec![Format::Key, Format::Crt, Format::DerKey]
+        );
+        assert!(parse_formats(&["p7b".to_string()]).is_err());
+        assert!(parse_formats(&[]).is_err());
+    }
+}
@@ -0,0 +1,323 @@
+use crate::cert::KeyType;
+use anyhow::{bail, Context, Result};
+use rcgen::KeyPair;
+use rsa::pkcs1::{DecodeRsaPublicKey, RsaPssParams};
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::pss::{Signature, SigningKey, VerifyingKey};
+use rsa::rand_core::OsRng;
+use rsa::signature::{RandomizedSigner, SignatureEncoding, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::digest::const_oid::AssociatedOid;
+use sha2::digest::{Digest, FixedOutputReset};
+use sha2::{Sha256, Sha384, Sha512};
+use x509_cert::crl::CertificateList;
+use x509_cert::der::asn1::{BitString, ObjectIdentifier};
+use x509_cert::der::{Decode, Encode};
+use x509_cert::request::CertReq;
+use x509_cert::spki::AlgorithmIdentifierOwned;
+use x509_cert::Certificate;
+
+/// id-RSASSA-PSS (RFC 4055)
+const RSASSA_PSS: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.2.840.113549.1.1.10");
+
+// rcgen's backend cannot produce RSASSA-PSS signatures, so certificates and
+// CSRs for RSA-PSS key types (and everything an RSA-PSS CA issues) are built
+// with PKCS#1 v1.5 and re-signed here.
+
+/// Whether certificates signed by this key type need re-signing with RSA-PSS
+pub fn is_pss(key_type: KeyType) -> bool {
+    matches!(
+        key_type,
+        KeyType::RsaPssSha256 | KeyType::RsaPssSha384 | KeyType::RsaPssSha512
+    )
+}
+
+/// Re-sign a DER certificate with RSA-PSS
+pub fn resign_certificate(der: &[u8], key_pair: &KeyPair, key_type: KeyType) -> Result<Vec<u8>> {
+    let mut cert = Certificate::from_der(der).context("Failed to parse certificate")?;
+    let algorithm = algorithm(key_type)?;
+    cert.tbs_certificate.signature = algorithm.clone();
+    cert.signature_algorithm = algorithm;
+
+    let tbs = cert.tbs_certificate.to_der()?;
+    cert.signature = BitString::from_bytes(&sign(key_pair, key_type, &tbs)?)?;
+    Ok(cert.to_der()?)
+}
+
+/// Re-sign a DER certificate signing request with RSA-PSS
+pub fn resign_csr(der: &[u8], key_pair: &KeyPair, key_type: KeyType) -> Result<Vec<u8>> {
+    let mut csr = CertReq::from_der(der).context("Failed to parse CSR")?;
+    csr.algorithm = algorithm(key_type)?;
+
+    let info = csr.info.to_der()?;
+    csr.signature = BitString::from_bytes(&sign(key_pair, key_type, &info)?)?;
+    Ok(csr.to_der()?)
+}
+
+/// Re-sign a DER certificate revocation list with RSA-PSS
+pub fn resign_crl(der: &[u8], key_pair: &KeyPair, key_type: KeyType) -> Result<Vec<u8>> {
+    let mut crl = CertificateList::from_der(der).context("Failed to parse CRL")?;
+    let algorithm = algorithm(key_type)?;
+    crl.tbs_cert_list.signature = algorithm.clone();
+    crl.signature_algorithm = algorithm;
+
+    let tbs = crl.tbs_cert_list.to_der()?;
+    crl.signature = BitString::from_bytes(&sign(key_pair, key_type, &tbs)?)?;
+    Ok(crl.to_der()?)
+}
+
+/// Whether a DER certificate signing request is signed with RSA-PSS
+pub fn is_pss_csr(der: &[u8]) -> bool {
+    CertReq::from_der(der).is_ok_and(|csr| csr.algorithm.oid == RSASSA_PSS)
+}
+
+/// Verify the RSA-PSS signature of a DER certificate signing request
+pub fn verify_csr(der: &[u8]) -> Result<()> {
+    let csr = CertReq::from_der(der).context("Failed to parse CSR")?;
+    let params = csr
+        .algorithm
+        .parameters
+        .as_ref()
+        .context("RSA-PSS CSR without signature parameters")?
+        .to_der()?;
+    let params = RsaPssParams::from_der(&params)
+        .map_err(|e| anyhow::anyhow!("Invalid RSA-PSS parameters: {}", e))?;
+    // The key may be labelled rsaEncryption or RSASSA-PSS; both hold a PKCS#1 key
+    let public_key =
+        RsaPublicKey::from_pkcs1_der(csr.info.public_key.subject_public_key.raw_bytes())
+            .map_err(|e| anyhow::anyhow!("RSA-PSS CSR without an RSA key: {}", e))?;
+    let signature = Signature::try_from(csr.signature.raw_bytes())
+        .map_err(|e| anyhow::anyhow!("Invalid CSR signature: {}", e))?;
+    let info = csr.info.to_der()?;
+
+    let salt_len = params.salt_len as usize;
+    let verified = match params.hash.oid {
+        Sha256::OID => VerifyingKey::<Sha256>::new_with_salt_len(public_key, salt_len)
+            .verify(&info, &signature),
+        Sha384::OID => VerifyingKey::<Sha384>::new_with_salt_len(public_key, salt_len)
+            .verify(&info, &signature),
+        Sha512::OID => VerifyingKey::<Sha512>::new_with_salt_len(public_key, salt_len)
+            .verify(&info, &signature),
+        oid => bail!("Unsupported RSA-PSS hash {} in CSR", oid),
+    };
+    verified.map_err(|_| anyhow::anyhow!("CSR signature verification failed"))
+}
+
+/// RSASSA-PSS algorithm identifier (MGF1 with the same hash, salt length = hash length)
+pub fn algorithm(key_type: KeyType) -> Result<AlgorithmIdentifierOwned> {
+    let algorithm = match key_type {
+        KeyType::RsaPssSha256 => rsa::pss::get_default_pss_signature_algo_id::<Sha256>(),
+        KeyType::RsaPssSha384 => rsa::pss::get_default_pss_signature_algo_id::<Sha384>(),
+        KeyType::RsaPssSha512 => rsa::pss::get_default_pss_signature_algo_id::<Sha512>(),
+        _ => bail!("{:?} does not sign with RSA-PSS", key_type),
+    };
+    algorithm.map_err(|e| anyhow::anyhow!("Failed to encode RSA-PSS parameters: {}", e))
+}
+
+fn sign(key_pair: &KeyPair, key_type: KeyType, message: &[u8]) -> Result<Vec<u8>> {
+    let private_key = RsaPrivateKey::from_pkcs8_der(&key_pair.serialize_der())
+        .map_err(|e| anyhow::anyhow!("RSA-PSS requires an RSA key: {}", e))?;
+    match key_type {
+        KeyType::RsaPssSha256 => Ok(sign_with::<Sha256>(private_key, message)),
+        KeyType::RsaPssSha384 => Ok(sign_with::<Sha384>(private_key, message)),
+        KeyType::RsaPssSha512 => Ok(sign_with::<Sha512>(private_key, message)),
+        _ => bail!("{:?} does not sign with RSA-PSS", key_type),
+    }
+}
+
+fn sign_with<D: Digest + FixedOutputReset>(private_key: RsaPrivateKey, message: &[u8]) -> Vec<u8> {
+    SigningKey::<D>::new(private_key)
+        .sign_with_rng(&mut OsRng, message)
+        .to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cert::{self, CertOptions};
+    use rsa::pss::{Signature, VerifyingKey};
+    use rsa::signature::Verifier;
+    use rsa::RsaPublicKey;
+    use x509_cert::spki::DecodePublicKey;
+
+    fn options(key_type: KeyType) -> CertOptions {
+        CertOptions {
+            cn: "pss.local".to_string(),
+            sans: vec!["pss.local".to_string()],
+            validity_days: 30,
+            key_type,
+            ..CertOptions::default()
+        }
+    }
+
+    fn public_key(key_pem: &str) -> RsaPublicKey {
+        let key_pair = KeyPair::from_pem(key_pem).unwrap();
+        RsaPublicKey::from_public_key_der(&key_pair.public_key_der()).unwrap()
+    }
+
+    #[test]
+    fn test_pss_certificate_signature_verifies() {
+        let generated = cert::generate_cert(options(KeyType::RsaPssSha512)).unwrap();
+        let der = pem::parse(&generated.cert_pem).unwrap();
+        let cert = Certificate::from_der(der.contents()).unwrap();
+        assert_eq!(
+            cert.signature_algorithm,
+            algorithm(KeyType::RsaPssSha512).unwrap()
+        );
+
+        let tbs = cert.tbs_certificate.to_der().unwrap();
+        let signature = Signature::try_from(cert.signature.raw_bytes()).unwrap();
+        let verifier = VerifyingKey::<Sha512>::new(public_key(&generated.key_pem));
+        assert!(verifier.verify(&tbs, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_pss_ca_signs_everything_with_pss() {
+        use crate::ca::{self, CaOptions};
+        use crate::crl::{self, RevocationDb};
+
+        let dir = std::env::temp_dir().join(format!("suresign-pss-ca-{}", std::process::id()));
+        let root = ca::create_root(CaOptions {
+            cn: "PSS Test CA".to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type: KeyType::RsaPssSha384,
+        })
+        .unwrap();
+        ca::save(&dir, &root).unwrap();
+        let root = ca::load(&dir).unwrap();
+        let issuer = ca::load(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(root.key_type, KeyType::RsaPssSha384);
+
+        let ca_key = RsaPublicKey::from_public_key_der(&root.key_pair.public_key_der()).unwrap();
+        let verifier = VerifyingKey::<Sha384>::new(ca_key);
+        let verify = |tbs: Vec<u8>, signature: &[u8]| {
+            let signature = Signature::try_from(signature).unwrap();
+            assert!(verifier.verify(&tbs, &signature).is_ok());
+        };
+
+        let mut opts = options(KeyType::Ecdsa);
+        opts.issuer = Some(issuer);
+        let leaf = cert::generate_cert(opts).unwrap();
+        let cert = Certificate::from_der(&leaf.cert_der).unwrap();
+        assert_eq!(
+            cert.signature_algorithm,
+            algorithm(KeyType::RsaPssSha384).unwrap()
+        );
+        verify(
+            cert.tbs_certificate.to_der().unwrap(),
+            cert.signature.raw_bytes(),
+        );
+
+        let crl = crl::build_crl(&root, &RevocationDb::default(), 7).unwrap();
+        let crl = CertificateList::from_der(&crl).unwrap();
+        assert_eq!(
+            crl.signature_algorithm,
+            algorithm(KeyType::RsaPssSha384).unwrap()
+        );
+        verify(
+            crl.tbs_cert_list.to_der().unwrap(),
+            crl.signature.raw_bytes(),
+        );
+
+        let ca_options = |key_type| CaOptions {
+            cn: "PSS Intermediate".to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type,
+        };
+        let intermediate =
+            ca::create_intermediate(&root, ca_options(KeyType::Ecdsa), None).unwrap();
+        let der = pem::parse(&intermediate.cert_pem).unwrap().into_contents();
+        let cert = Certificate::from_der(&der).unwrap();
+        verify(
+            cert.tbs_certificate.to_der().unwrap(),
+            cert.signature.raw_bytes(),
+        );
+    }
+
+    #[test]
+    fn test_ca_scheme_survives_save_and_load() {
+        use crate::ca::{self, CaOptions};
+
+        let ca_options = |cn: &str, key_type| CaOptions {
+            cn: cn.to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type,
+        };
+        let dir = std::env::temp_dir().join(format!("suresign-pss-scheme-{}", std::process::id()));
+        let root = ca::create_root(ca_options("ECDSA Root", KeyType::Ecdsa)).unwrap();
+        let pss = ca::create_intermediate(&root, ca_options("PSS", KeyType::RsaPssSha256), Some(1));
+        ca::save(&dir, &pss.unwrap()).unwrap();
+        let pss = ca::load(&dir).unwrap();
+        assert_eq!(pss.key_type, KeyType::RsaPssSha256);
+
+        // An intermediate's scheme is its own, not the one its parent signed it with
+        let rsa = ca::create_intermediate(&pss, ca_options("RSA", KeyType::Rsa), None);
+        ca::save(&dir, &rsa.unwrap()).unwrap();
+        assert_eq!(ca::load(&dir).unwrap().key_type, KeyType::Rsa);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_pss_csr_signature_verifies() {
+        let generated = cert::generate_csr(options(KeyType::RsaPssSha256)).unwrap();
+        let der = pem::parse(&generated.csr_pem).unwrap();
+        let csr = CertReq::from_der(der.contents()).unwrap();
+        assert_eq!(csr.algorithm, algorithm(KeyType::RsaPssSha256).unwrap());
+
+        let info = csr.info.to_der().unwrap();
+        let signature = Signature::try_from(csr.signature.raw_bytes()).unwrap();
+        let verifier = VerifyingKey::<Sha256>::new(public_key(&generated.key_pem));
+        assert!(verifier.verify(&info, &signature).is_ok());
+    }
+
+    #[test]
+    fn test_pss_csr_can_be_signed() {
+        use crate::ca::{self, CaOptions};
+        use crate::cert::SignOptions;
+        use crate::usage::Purpose;
+
+        let generated = cert::generate_csr(options(KeyType::RsaPssSha384)).unwrap();
+        let parsed = cert::parse_csr(generated.csr_pem.as_bytes()).unwrap();
+        let root = ca::create_root(CaOptions {
+            cn: "CSR Test CA".to_string(),
+            validity_days: 30,
+            organization: None,
+            key_type: KeyType::Ecdsa,
+        })
+        .unwrap();
+        let issued = cert::sign_csr(
+            parsed,
+            SignOptions {
+                validity_days: 30,
+                sans: None,
+                purpose: Purpose::Server,
+                key_usages: None,
+                extended_key_usages: None,
+            },
+            &root,
+        )
+        .unwrap();
+
+        let issued = Certificate::from_der(&issued.cert_der).unwrap();
+        let key_pair = KeyPair::from_pem(&generated.key_pem).unwrap();
+        assert_eq!(
+            issued
+                .tbs_certificate
+                .subject_public_key_info
+                .to_der()
+                .unwrap(),
+            key_pair.public_key_der()
+        );
+        assert_eq!(issued.tbs_certificate.subject.to_string(), "CN=pss.local");
+
+        // A tampered request is refused
+        let mut der = pem::parse(&generated.csr_pem).unwrap().into_contents();
+        let last = der.len() - 1;
+        der[last] ^= 1;
+        assert!(cert::parse_csr(&der).is_err());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::ca;
+use crate::cert::{self, PfxFormat};
+use crate::cli::RenewArgs;
+use crate::i18n::t;
+use crate::inventory;
+use crate::key;
+use crate::output::{self, Format};
+use anyhow::{bail, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Handle `suresign renew <name>`
+pub fn run(
+    args: RenewArgs,
+    default_output: Option<PathBuf>,
+    default_pfx_password: Option<String>,
+    pfx_format: PfxFormat,
+    ca_dir: &Path,
+    inventory: &Path,
+    quiet: bool,
+) -> Result<()> {
+    let output_path = args
+        .output
+        .or(default_output)
+        .unwrap_or_else(|| PathBuf::from("."));
+
+    let key_path = output_path.join(format!("{}.key", args.name));
+    let crt_path = output_path.join(format!("{}.crt", args.name));
+    let pem_path = output_path.join(format!("{}.pem", args.name));
+    let pfx_path = output_path.join(format!("{}.pfx", args.name));
+    let fullchain_path = output_path.join(format!("{}-fullchain.pem", args.name));
+    let der_path = output_path.join(Format::Der.file_name(&args.name));
+
+    let key_data =
+        fs::read(&key_path).with_context(|| format!("Failed to read {}", key_path.display()))?;
+    let cert_pem = fs::read_to_string(&crt_path)
+        .with_context(|| format!("Failed to read {}", crt_path.display()))?;
+
+    // Re-issue from the same CA that signed the current certificate
+    let cert_der = pem::parse(&cert_pem)
+        .context("Failed to parse certificate")?
+        .into_contents();
+    let (_, x509) = x509_parser::parse_x509_certificate(&cert_der)
+        .map_err(|e| anyhow::anyhow!("Failed to parse certificate: {}", e))?;
+    let issuer = if x509.issuer().as_raw() == x509.subject().as_raw() {
+        None
+    } else {
+        match ca::find_issuer(ca_dir, x509.issuer().as_raw())? {
+            Some(ca) => Some(ca),
+            None => bail!(
+                "The issuing CA ({}) was not found in {}",
+                x509.issuer(),
+                ca_dir.display()
+            ),
+        }
+    };
+
+    if !quiet {
+        println!("{}", t("renewing"));
+    }
+
+    let passphrase = key::resolve_passphrase(&key_data, None, true);
+    let key_pair = key::load_private_key(&key_data, passphrase.as_deref())
+        .context("Failed to load private key")?;
+
+    let renewed = cert::renew_cert(
+        &cert_pem,
+        &key_pair,
+        args.days,
+        issuer.as_ref(),
+        &args
+            .pfx_password
+            .or(default_pfx_password)
+            .unwrap_or_default(),
+        pfx_format,
+    )
+    .context("Failed to renew certificate")?;
+    let has_chain = !renewed.chain_pem.is_empty();
+
+    // A DER copy written alongside is kept in sync
+    let has_der = der_path.exists();
+
+    // The key file is left untouched
+    let mut paths = vec![&crt_path, &pem_path, &pfx_path];
+    if has_der {
+        paths.push(&der_path);
+    }
+    if has_chain {
+        paths.push(&fullchain_path);
+    }
+    if !output::confirm_overwrite(&paths, args.yes)? {
+        return Ok(());
+    }
+
+    fs::write(&crt_path, &renewed.cert_pem).context("Failed to write crt file")?;
+    // Keep the bundled key encrypted if the key file is
+    let key_pem = if key::is_encrypted(&key_data) {
+        let (_, der) = key::detect(&key_data)?;
+        cert::to_pem("ENCRYPTED PRIVATE KEY", &der)
+    } else {
+        renewed.key_pem.clone()
+    };
+    let bundle = format!("{}\n{}", key_pem, renewed.cert_pem);
+    fs::write(&pem_path, bundle).context("Failed to write pem file")?;
+    fs::write(&pfx_path, &renewed.pfx).context("Failed to write pfx file")?;
+    if has_der {
+        fs::write(&der_path, &renewed.cert_der).context("Failed to write der file")?;
+    }
+    if has_chain {
+        let fullchain = format!("{}{}", renewed.cert_pem, renewed.chain_pem.concat());
+        fs::write(&fullchain_path, fullchain).context("Failed to write fullchain file")?;
+    }
+    inventory::record(inventory, &args.name, &crt_path, &renewed.cert_der);
+
+    if !quiet {
+        output::print_saved(&output_path, &paths)?;
+    }
+
+    Ok(())
+}
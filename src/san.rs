@@ -0,0 +1,477 @@
+use crate::usage::parse_oid;
+use anyhow::{bail, Result};
+use rcgen::{OtherNameValue, SanType};
+use std::net::IpAddr;
+use std::str::FromStr;
+
+// Subject Alternative Name syntax. Entries may carry a type prefix
+// (`dns:`, `ip:`, `email:`, `uri:`, `othername:`); untyped entries are
+// IP addresses or DNS names. Internationalized DNS names are stored as
+// punycode A-labels.
+
+/// Type prefixes accepted in SAN entries
+pub const SAN_PREFIXES: [&str; 5] = ["dns", "ip", "email", "uri", "othername"];
+
+/// Common multi-label public suffixes that a wildcard must not sit directly on.
+/// This is a small best-effort subset of the Public Suffix List, not the full list,
+/// so the warning can miss rarer suffixes. Every single-label name (a top-level
+/// domain) is treated as a public suffix as well.
+const PUBLIC_SUFFIXES: &[&str] = &[
+    // Country-code second-level domains
+    "co.uk",
+    "org.uk",
+    "ac.uk",
+    "gov.uk",
+    "me.uk",
+    "net.uk",
+    "ltd.uk",
+    "plc.uk",
+    "com.au",
+    "net.au",
+    "org.au",
+    "edu.au",
+    "gov.au",
+    "co.nz",
+    "net.nz",
+    "org.nz",
+    "co.jp",
+    "ne.jp",
+    "or.jp",
+    "ac.jp",
+    "ad.jp",
+    "ed.jp",
+    "go.jp",
+    "gr.jp",
+    "lg.jp",
+    "co.kr",
+    "ne.kr",
+    "or.kr",
+    "com.cn",
+    "net.cn",
+    "org.cn",
+    "gov.cn",
+    "edu.cn",
+    "com.tw",
+    "net.tw",
+    "org.tw",
+    "com.hk",
+    "net.hk",
+    "org.hk",
+    "com.sg",
+    "com.my",
+    "co.in",
+    "net.in",
+    "org.in",
+    "co.id",
+    "or.id",
+    "com.ph",
+    "com.vn",
+    "co.th",
+    "in.th",
+    "co.za",
+    "org.za",
+    "com.br",
+    "net.br",
+    "org.br",
+    "com.mx",
+    "com.ar",
+    "com.tr",
+    "co.il",
+    "com.ua",
+    // Hosting platforms that hand out sub-domains
+    "github.io",
+    "gitlab.io",
+    "herokuapp.com",
+    "netlify.app",
+    "vercel.app",
+    "pages.dev",
+    "workers.dev",
+    "web.app",
+    "firebaseapp.com",
+    "appspot.com",
+    "azurewebsites.net",
+    "cloudfront.net",
+    "blogspot.com",
+    "s3.amazonaws.com",
+    "ngrok.io",
+    "ngrok-free.app",
+    "fly.dev",
+    "onrender.com",
+    "glitch.me",
+    "duckdns.org",
+    "dyndns.org",
+    "no-ip.org",
+];
+
+/// Parse a SAN entry such as `app.local`, `10.0.0.1`, `email:alice@example.com`,
+/// `uri:spiffe://example.org/app` or `othername:1.3.6.1.4.1.311.20.2.3;UTF8:alice@corp`
+pub fn parse_san(entry: &str) -> Result<SanType> {
+    let entry = entry.trim();
+    san_entry(entry).map_err(|e| anyhow::anyhow!("Invalid SAN '{}': {}", entry, e))
+}
+
+fn san_entry(entry: &str) -> Result<SanType> {
+    match split_prefix(entry) {
+        Some(("dns", name)) => dns_name(name),
+        Some(("ip", ip)) => IpAddr::from_str(ip)
+            .map_err(|_| anyhow::anyhow!("not an IPv4 or IPv6 address"))
+            .map(SanType::IpAddress),
+        Some(("email", email)) => email_address(email),
+        Some(("uri", uri)) => uri_name(uri),
+        Some(("othername", value)) => other_name(value),
+        _ => match IpAddr::from_str(entry) {
+            Ok(ip) => Ok(SanType::IpAddress(ip)),
+            Err(_) if entry.contains("://") => Err(anyhow::anyhow!("use uri: for URIs")),
+            Err(_) if entry.contains('@') => Err(anyhow::anyhow!("use email: for email addresses")),
+            Err(_) => dns_name(entry),
+        },
+    }
+}
+
+/// Parse the CN of a server certificate, which doubles as its first SAN and is
+/// held to the same rules: a host name (wildcards included) or an IP address
+pub fn parse_host_cn(cn: &str) -> Result<SanType> {
+    let cn = cn.trim();
+    let parsed = match split_prefix(cn) {
+        Some(_) => Err(anyhow::anyhow!(
+            "a server CN must be a host name or IP address"
+        )),
+        None => san_entry(cn),
+    };
+    parsed.map_err(|e| anyhow::anyhow!("Invalid CN '{}': {}", cn, e))
+}
+
+/// Check a URL written into a certificate extension (AIA, CRL Distribution Points)
+pub fn check_url(url: &str) -> Result<()> {
+    uri_name(url)
+        .map(|_| ())
+        .map_err(|e| anyhow::anyhow!("Invalid URL '{}': {}", url, e))
+}
+
+/// Format a SAN entry for display (and in the syntax `parse_san` accepts)
+pub fn san_to_string(san: &SanType) -> String {
+    match san {
+        SanType::DnsName(name) => format!("DNS:{}", name.as_str()),
+        SanType::IpAddress(ip) => format!("IP:{}", ip),
+        SanType::Rfc822Name(email) => format!("email:{}", email.as_str()),
+        SanType::URI(uri) => format!("URI:{}", uri.as_str()),
+        SanType::OtherName((oid, value)) => {
+            let oid = oid
+                .iter()
+                .map(|n| n.to_string())
+                .collect::<Vec<_>>()
+                .join(".");
+            match value {
+                OtherNameValue::Utf8String(s) => format!("othername:{};UTF8:{}", oid, s),
+                _ => format!("othername:{}", oid),
+            }
+        }
+        _ => "(unsupported)".to_string(),
+    }
+}
+
+/// Split a known, case-insensitive type prefix (`DNS:`, `email:`, ...) off an entry.
+/// IPv6 addresses such as `::1` or `fe80::1` are not mistaken for prefixes.
+fn split_prefix(entry: &str) -> Option<(&'static str, &str)> {
+    let (prefix, rest) = entry.split_once(':')?;
+    let prefix = prefix.trim().to_lowercase();
+    let known = SAN_PREFIXES.iter().find(|p| **p == prefix)?;
+    Some((known, rest.trim()))
+}
+
+/// Convert a Unicode host name to its A-label form (UTS #46), keeping a
+/// leading wildcard label. ASCII names are returned unchanged.
+pub fn to_ascii_hostname(name: &str) -> Result<String> {
+    if name.is_ascii() {
+        return Ok(name.to_string());
+    }
+    let (wildcard, rest) = match name.strip_prefix("*.") {
+        Some(rest) => ("*.", rest),
+        None => ("", name),
+    };
+    let ascii = idna::domain_to_ascii(rest)
+        .map_err(|_| anyhow::anyhow!("'{}' is not a valid internationalized domain name", name))?;
+    Ok(format!("{}{}", wildcard, ascii))
+}
+
+/// Unicode DNS names among the SAN entries, with the A-label each is stored as
+pub fn idn_names(entries: &[String]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .map(|entry| entry.trim())
+        .filter_map(|entry| match split_prefix(entry) {
+            Some(("dns", name)) => Some(name),
+            None => Some(entry),
+            _ => None,
+        })
+        .filter(|name| !name.is_ascii())
+        .filter_map(|name| {
+            to_ascii_hostname(name)
+                .ok()
+                .map(|ascii| (name.to_string(), ascii))
+        })
+        .collect()
+}
+
+fn dns_name(name: &str) -> Result<SanType> {
+    let name = to_ascii_hostname(name)?;
+    if name.is_empty() {
+        bail!("empty DNS name");
+    }
+    if name.len() > 253 {
+        bail!("DNS name is longer than 253 characters");
+    }
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() {
+            bail!("empty label in DNS name");
+        }
+        if label.len() > 63 {
+            bail!("DNS label '{}' is longer than 63 characters", label);
+        }
+        if let Some(c) = label
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '*')))
+        {
+            bail!("'{}' is not allowed in a DNS name", c);
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            bail!("DNS label '{}' starts or ends with '-'", label);
+        }
+    }
+    check_wildcard(&name)?;
+    Ok(SanType::DnsName(name.try_into()?))
+}
+
+/// A wildcard may only be the whole left-most label, and needs a domain below it
+fn check_wildcard(name: &str) -> Result<()> {
+    if !name.contains('*') {
+        return Ok(());
+    }
+    let Some(base) = wildcard_base(name) else {
+        bail!("a wildcard must be the whole left-most label (e.g. *.example.local)");
+    };
+    if base.contains('*') {
+        bail!("only the left-most label may be a wildcard");
+    }
+    Ok(())
+}
+
+/// The domain a wildcard name covers (`*.dev.example` → `dev.example`)
+pub fn wildcard_base(name: &str) -> Option<&str> {
+    name.strip_prefix("*.").filter(|base| !base.is_empty())
+}
+
+/// Whether a domain is a public suffix (a top-level domain or a listed suffix)
+pub fn is_public_suffix(domain: &str) -> bool {
+    let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+    !domain.contains('.') || PUBLIC_SUFFIXES.contains(&domain.as_str())
+}
+
+/// Wildcard DNS entries that sit directly on a public suffix, with that suffix
+pub fn public_suffix_wildcards(entries: &[String]) -> Vec<(String, String)> {
+    entries
+        .iter()
+        .filter_map(|entry| match parse_san(entry) {
+            Ok(SanType::DnsName(name)) => Some(name.as_str().to_string()),
+            _ => None,
+        })
+        .filter_map(|name| {
+            let base = wildcard_base(&name)?.to_string();
+            is_public_suffix(&base).then_some((name, base))
+        })
+        .collect()
+}
+
+fn email_address(email: &str) -> Result<SanType> {
+    let Some((local, domain)) = email.rsplit_once('@') else {
+        bail!("email address needs a '@'");
+    };
+    if local.is_empty() || domain.is_empty() {
+        bail!("email address needs a local part and a domain");
+    }
+    if email.chars().any(|c| c.is_whitespace() || !c.is_ascii()) {
+        bail!("email address must be ASCII without spaces");
+    }
+    Ok(SanType::Rfc822Name(email.try_into()?))
+}
+
+fn uri_name(uri: &str) -> Result<SanType> {
+    // RFC 3986: scheme = ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ), followed by ':'
+    let valid_scheme = uri.split_once(':').is_some_and(|(scheme, rest)| {
+        !rest.is_empty()
+            && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    });
+    if !valid_scheme {
+        bail!("URI needs a scheme (e.g. https:// or spiffe://)");
+    }
+    if uri.chars().any(|c| c.is_whitespace() || !c.is_ascii()) {
+        bail!("URI must be ASCII without spaces");
+    }
+    Ok(SanType::URI(uri.try_into()?))
+}
+
+/// `<oid>;UTF8:<value>` (OpenSSL syntax) or `<oid>;<value>`
+fn other_name(value: &str) -> Result<SanType> {
+    let Some((oid, value)) = value.split_once(';') else {
+        bail!("othername needs '<oid>;UTF8:<value>'");
+    };
+    let Some(oid) = parse_oid(oid.trim()) else {
+        bail!("'{}' is not a dotted OID", oid.trim());
+    };
+    let value = match value.split_once(':') {
+        Some((kind, v)) if kind.eq_ignore_ascii_case("utf8") => v,
+        Some((kind, _)) if kind.chars().all(|c| c.is_ascii_alphanumeric()) => {
+            bail!("othername values must be UTF8 (got {})", kind)
+        }
+        _ => value,
+    };
+    Ok(SanType::OtherName((oid, value.into())))
+}
+
+/// Names a LAN dev server is reached by: the hostname, `<hostname>.local`,
+/// localhost, the loopback addresses and every non-loopback interface address
+pub fn local_sans() -> Vec<String> {
+    let hostname = gethostname::gethostname().to_string_lossy().into_owned();
+    let addrs: Vec<IpAddr> = if_addrs::get_if_addrs()
+        .map(|ifaces| ifaces.iter().map(|i| i.ip()).collect())
+        .unwrap_or_default();
+    local_names(&hostname, &addrs)
+}
+
+fn local_names(hostname: &str, addrs: &[IpAddr]) -> Vec<String> {
+    let hostname = hostname.trim().trim_end_matches('.').to_lowercase();
+    let mut names = Vec::new();
+    if !hostname.is_empty() && hostname != "localhost" {
+        names.push(hostname.clone());
+        // mDNS name, only for single-label hostnames
+        if !hostname.contains('.') {
+            names.push(format!("{}.local", hostname));
+        }
+    }
+    names.extend(["localhost", "127.0.0.1", "::1"].map(String::from));
+    for addr in addrs {
+        // Link-local IPv6 addresses need a zone index, so certificates can't name them
+        let link_local = matches!(addr, IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80);
+        let name = addr.to_string();
+        if !addr.is_loopback() && !link_local && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_typed_sans() {
+        let entries = [
+            "app.local",
+            "10.0.0.1",
+            "::1",
+            "DNS:api.local",
+            "ip:fe80::1",
+            "email:alice@example.com",
+            "URI:spiffe://example.org/ns/default/sa/app",
+            "othername:1.3.6.1.4.1.311.20.2.3;UTF8:alice@corp.example",
+        ];
+        let parsed: Vec<String> = entries
+            .iter()
+            .map(|e| san_to_string(&parse_san(e).unwrap()))
+            .collect();
+        assert_eq!(
+            parsed,
+            vec![
+                "DNS:app.local",
+                "IP:10.0.0.1",
+                "IP:::1",
+                "DNS:api.local",
+                "IP:fe80::1",
+                "email:alice@example.com",
+                "URI:spiffe://example.org/ns/default/sa/app",
+                "othername:1.3.6.1.4.1.311.20.2.3;UTF8:alice@corp.example",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_idn_sans_become_a_labels() {
+        let san = parse_san("例え.jp").unwrap();
+        assert_eq!(san_to_string(&san), "DNS:xn--r8jz45g.jp");
+        let san = parse_san("dns:*.Bücher.example").unwrap();
+        assert_eq!(san_to_string(&san), "DNS:*.xn--bcher-kva.example");
+
+        let entries = vec![
+            "例え.jp".to_string(),
+            "app.local".to_string(),
+            "email:me@example.com".to_string(),
+        ];
+        assert_eq!(
+            idn_names(&entries),
+            vec![("例え.jp".to_string(), "xn--r8jz45g.jp".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_wildcards_on_public_suffixes() {
+        let entries: Vec<String> = ["*.dev.example.local", "*.co.uk", "*.com", "*.GitHub.io"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let found: Vec<String> = public_suffix_wildcards(&entries)
+            .into_iter()
+            .map(|(_, suffix)| suffix)
+            .collect();
+        assert_eq!(found, vec!["co.uk", "com", "GitHub.io"]);
+    }
+
+    #[test]
+    fn test_invalid_san_names_the_entry() {
+        for entry in [
+            "spiffe://example.org/app",
+            "email:alice",
+            "uri:not a uri",
+            "ip:300.1.1.1",
+            "othername:upn;alice",
+            "bad host.local",
+            "api.*.example.local",
+            "f*o.example.local",
+            "*",
+        ] {
+            let err = parse_san(entry).unwrap_err().to_string();
+            assert!(
+                err.starts_with(&format!("Invalid SAN '{}'", entry)),
+                "{}",
+                err
+            );
+        }
+    }
+
+    #[test]
+    fn test_local_names() {
+        let addrs: Vec<IpAddr> = ["127.0.0.1", "192.168.1.20", "::1", "fe80::1", "fd00::20"]
+            .iter()
+            .map(|a| a.parse().unwrap())
+            .collect();
+        assert_eq!(
+            local_names("DevBox", &addrs),
+            vec![
+                "devbox",
+                "devbox.local",
+                "localhost",
+                "127.0.0.1",
+                "::1",
+                "192.168.1.20",
+                "fd00::20"
+            ]
+        );
+        assert_eq!(
+            local_names("localhost", &[]),
+            vec!["localhost", "127.0.0.1", "::1"]
+        );
+    }
+}
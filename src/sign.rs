@@ -0,0 +1,148 @@
+use crate::ca;
+use crate::cert::{self, SignOptions};
+use crate::cli::SignArgs;
+use crate::i18n::t;
+use crate::inventory;
+use crate::output::{self, Format};
+use crate::san;
+use crate::usage;
+use anyhow::{Context, Result};
+use inquire::Confirm;
+use rcgen::{ExtendedKeyUsagePurpose, KeyUsagePurpose};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Handle `suresign sign <csr>`
+pub fn run(args: SignArgs, ca_dir: &Path, inventory: &Path, quiet: bool) -> Result<()> {
+    let data = fs::read(&args.csr).context("Failed to read CSR file")?;
+    let csr = cert::parse_csr(&data).context("Failed to parse CSR")?;
+
+    let issuer_dir = match &args.intermediate {
+        Some(name) => ca::intermediate_dir(ca_dir, name),
+        None => ca_dir.to_path_buf(),
+    };
+    let issuer = ca::load(&issuer_dir)?;
+
+    let opt = SignOptions {
+        validity_days: args.days.unwrap_or(365),
+        sans: args.sans,
+        purpose: args
+            .purpose
+            .as_deref()
+            .map(usage::parse_purpose)
+            .unwrap_or_default(),
+        key_usages: match &args.key_usage {
+            Some(names) => Some(usage::parse_key_usages(names)?),
+            None => None,
+        },
+        extended_key_usages: match &args.extended_key_usage {
+            Some(names) => Some(usage::parse_extended_key_usages(names)?),
+            None => None,
+        },
+    };
+    let (key_usages, extended_key_usages) = opt.usages(&csr);
+
+    // Show what the requester asked for before signing anything
+    let requested_sans: Vec<String> = csr
+        .params
+        .subject_alt_names
+        .iter()
+        .map(san::san_to_string)
+        .collect();
+    println!(
+        "{} {}",
+        t("csr_subject"),
+        cert::dn_to_string(&csr.params.distinguished_name)
+    );
+    println!("{} {}", t("csr_sans"), requested_sans.join(", "));
+    if let Some(sans) = &opt.sans {
+        println!("{} {}", t("csr_sans_override"), sans.join(", "));
+    }
+    // Requested usages are shown but not copied; the purpose or overrides decide
+    if !csr.params.key_usages.is_empty() || !csr.params.extended_key_usages.is_empty() {
+        println!(
+            "{} {}",
+            t("csr_usages"),
+            usage_names(&csr.params.key_usages, &csr.params.extended_key_usages)
+        );
+    }
+    println!(
+        "{} {}",
+        t("csr_usages_issued"),
+        usage_names(&key_usages, &extended_key_usages)
+    );
+    println!("{} {}", t("csr_issuer"), issuer.common_name());
+
+    if !args.yes {
+        let confirmed = Confirm::new(&t("sign_confirm"))
+            .with_default(false)
+            .prompt()
+            .unwrap_or(false);
+        if !confirmed {
+            println!("{}", t("aborted"));
+            return Ok(());
+        }
+    }
+
+    let issued = cert::sign_csr(csr, opt, &issuer).context("Failed to sign CSR")?;
+
+    let output_path = args.output.unwrap_or_else(|| PathBuf::from("."));
+    if !output_path.exists() {
+        fs::create_dir_all(&output_path).context("Failed to create output directory")?;
+    }
+
+    let file_name = args.name.unwrap_or_else(|| {
+        args.csr
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "server".to_string())
+    });
+    let crt_path = output_path.join(format!("{}.crt", file_name));
+    let der_path = output_path.join(Format::Der.file_name(&file_name));
+    let fullchain_path = output_path.join(format!("{}-fullchain.pem", file_name));
+    let has_chain = !issued.chain_pem.is_empty();
+
+    let mut paths = vec![&crt_path];
+    if args.der {
+        paths.push(&der_path);
+    }
+    if has_chain {
+        paths.push(&fullchain_path);
+    }
+    if !output::confirm_overwrite(&paths, args.yes)? {
+        return Ok(());
+    }
+
+    fs::write(&crt_path, &issued.cert_pem).context("Failed to write crt file")?;
+    if args.der {
+        fs::write(&der_path, &issued.cert_der).context("Failed to write der file")?;
+    }
+    if has_chain {
+        let fullchain = format!("{}{}", issued.cert_pem, issued.chain_pem.concat());
+        fs::write(&fullchain_path, fullchain).context("Failed to write fullchain file")?;
+    }
+    inventory::record(inventory, &file_name, &crt_path, &issued.cert_der);
+
+    if !quiet {
+        output::print_saved(&output_path, &paths)?;
+    }
+
+    Ok(())
+}
+
+/// Key usages and extended key usages as one line, e.g. `digital-signature; server-auth`
+fn usage_names(
+    key_usages: &[KeyUsagePurpose],
+    extended_key_usages: &[ExtendedKeyUsagePurpose],
+) -> String {
+    let key_usages: Vec<&str> = key_usages.iter().map(usage::key_usage_name).collect();
+    let extended_key_usages: Vec<String> = extended_key_usages
+        .iter()
+        .map(usage::extended_key_usage_name)
+        .collect();
+    format!(
+        "{}; {}",
+        key_usages.join(", "),
+        extended_key_usages.join(", ")
+    )
+}
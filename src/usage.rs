@@ -0,0 +1,250 @@
+use crate::cert::KeyType;
+use anyhow::{bail, Result};
+use rcgen::{ExtendedKeyUsagePurpose, KeyUsagePurpose};
+
+/// What a certificate is for; decides the default Key Usage and Extended Key Usage
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Purpose {
+    /// TLS server (serverAuth)
+    #[default]
+    Server,
+    /// TLS client (clientAuth)
+    Client,
+    /// Code signing (codeSigning)
+    CodeSigning,
+    /// S/MIME (emailProtection)
+    Email,
+}
+
+/// Names accepted by `--purpose`
+pub const PURPOSE_NAMES: [&str; 4] = ["server", "client", "code-signing", "email"];
+
+/// Key Usage bits by `--key-usage` name, in RFC 5280 bit order
+pub const KEY_USAGES: [(&str, KeyUsagePurpose); 9] = [
+    ("digital-signature", KeyUsagePurpose::DigitalSignature),
+    ("content-commitment", KeyUsagePurpose::ContentCommitment),
+    ("key-encipherment", KeyUsagePurpose::KeyEncipherment),
+    ("data-encipherment", KeyUsagePurpose::DataEncipherment),
+    ("key-agreement", KeyUsagePurpose::KeyAgreement),
+    ("key-cert-sign", KeyUsagePurpose::KeyCertSign),
+    ("crl-sign", KeyUsagePurpose::CrlSign),
+    ("encipher-only", KeyUsagePurpose::EncipherOnly),
+    ("decipher-only", KeyUsagePurpose::DecipherOnly),
+];
+
+/// Extended Key Usages by `--extended-key-usage` name (custom OIDs are also accepted)
+pub const EXTENDED_KEY_USAGES: [(&str, ExtendedKeyUsagePurpose); 7] = [
+    ("server-auth", ExtendedKeyUsagePurpose::ServerAuth),
+    ("client-auth", ExtendedKeyUsagePurpose::ClientAuth),
+    ("code-signing", ExtendedKeyUsagePurpose::CodeSigning),
+    ("email-protection", ExtendedKeyUsagePurpose::EmailProtection),
+    ("time-stamping", ExtendedKeyUsagePurpose::TimeStamping),
+    ("ocsp-signing", ExtendedKeyUsagePurpose::OcspSigning),
+    ("any", ExtendedKeyUsagePurpose::Any),
+];
+
+/// Parse purpose string from CLI to Purpose enum
+pub fn parse_purpose(s: &str) -> Purpose {
+    match s.to_lowercase().as_str() {
+        "client" => Purpose::Client,
+        "code-signing" => Purpose::CodeSigning,
+        "email" => Purpose::Email,
+        _ => Purpose::Server,
+    }
+}
+
+/// Lowercase and drop separators so `keyEncipherment`, `key-encipherment`
+/// and `key_encipherment` all match
+fn normalize(name: &str) -> String {
+    name.trim()
+        .chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Parse `--key-usage` names (kebab-case or RFC 5280 names such as `digitalSignature`)
+pub fn parse_key_usages(names: &[String]) -> Result<Vec<KeyUsagePurpose>> {
+    let mut usages = Vec::new();
+    for name in names {
+        let wanted = normalize(name);
+        let usage = match wanted.as_str() {
+            // Former name of contentCommitment
+            "nonrepudiation" => KeyUsagePurpose::ContentCommitment,
+            _ => match KEY_USAGES.iter().find(|(n, _)| normalize(n) == wanted) {
+                Some((_, usage)) => *usage,
+                None => {
+                    let valid: Vec<&str> = KEY_USAGES.iter().map(|(n, _)| *n).collect();
+                    bail!(
+                        "Unknown key usage '{}' (expected {})",
+                        name.trim(),
+                        valid.join(", ")
+                    );
+                }
+            },
+        };
+        if !usages.contains(&usage) {
+            usages.push(usage);
+        }
+    }
+    Ok(usages)
+}
+
+/// Parse `--extended-key-usage` names or dotted OIDs (e.g. `1.3.6.1.4.1.311.20.2.2`)
+pub fn parse_extended_key_usages(names: &[String]) -> Result<Vec<ExtendedKeyUsagePurpose>> {
+    let mut usages = Vec::new();
+    for name in names {
+        let wanted = normalize(name);
+        let usage = if let Some(oid) = parse_oid(name.trim()) {
+            ExtendedKeyUsagePurpose::Other(oid)
+        } else if let Some((_, usage)) = EXTENDED_KEY_USAGES
+            .iter()
+            .find(|(n, _)| normalize(n) == wanted)
+        {
+            usage.clone()
+        } else {
+            let valid: Vec<&str> = EXTENDED_KEY_USAGES.iter().map(|(n, _)| *n).collect();
+            bail!(
+                "Unknown extended key usage '{}' (expected {} or a dotted OID)",
+                name.trim(),
+                valid.join(", ")
+            );
+        };
+        if !usages.contains(&usage) {
+            usages.push(usage);
+        }
+    }
+    Ok(usages)
+}
+
+/// Parse a dotted OID such as `1.3.6.1.5.5.7.3.1`
+pub fn parse_oid(s: &str) -> Option<Vec<u64>> {
+    let arcs = s
+        .split('.')
+        .map(|arc| arc.parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    // X.660: at least two arcs, the first 0-2, the second below 40 under 0 and 1
+    match arcs.as_slice() {
+        [0 | 1, second, ..] if *second < 40 => Some(arcs),
+        [2, _, ..] => Some(arcs),
+        _ => None,
+    }
+}
+
+/// Key Usage bits for a purpose. Key encipherment only applies to RSA keys
+/// (RSA key transport); ECDSA and Ed25519 keys only sign.
+pub fn default_key_usages(purpose: Purpose, key_type: KeyType) -> Vec<KeyUsagePurpose> {
+    let mut usages = vec![KeyUsagePurpose::DigitalSignature];
+    match purpose {
+        Purpose::Server | Purpose::Client => {
+            if key_type.is_rsa() {
+                usages.push(KeyUsagePurpose::KeyEncipherment);
+            }
+        }
+        Purpose::CodeSigning => {}
+        Purpose::Email => {
+            usages.push(KeyUsagePurpose::ContentCommitment);
+            if key_type.is_rsa() {
+                usages.push(KeyUsagePurpose::KeyEncipherment);
+            }
+        }
+    }
+    usages
+}
+
+/// Extended Key Usage for a purpose
+pub fn default_extended_key_usages(purpose: Purpose) -> Vec<ExtendedKeyUsagePurpose> {
+    vec![match purpose {
+        Purpose::Server => ExtendedKeyUsagePurpose::ServerAuth,
+        Purpose::Client => ExtendedKeyUsagePurpose::ClientAuth,
+        Purpose::CodeSigning => ExtendedKeyUsagePurpose::CodeSigning,
+        Purpose::Email => ExtendedKeyUsagePurpose::EmailProtection,
+    }]
+}
+
+/// Name of a Key Usage bit, for display
+pub fn key_usage_name(usage: &KeyUsagePurpose) -> &'static str {
+    KEY_USAGES
+        .iter()
+        .find(|(_, u)| u == usage)
+        .map(|(n, _)| *n)
+        .unwrap_or("?")
+}
+
+/// Name (or dotted OID) of an Extended Key Usage, for display
+pub fn extended_key_usage_name(usage: &ExtendedKeyUsagePurpose) -> String {
+    match usage {
+        ExtendedKeyUsagePurpose::Other(oid) => oid
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join("."),
+        _ => EXTENDED_KEY_USAGES
+            .iter()
+            .find(|(_, u)| u == usage)
+            .map(|(n, _)| n.to_string())
+            .unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_usages() {
+        assert_eq!(
+            parse_key_usages(&strings(&[
+                "digitalSignature",
+                "key-encipherment",
+                "nonRepudiation"
+            ]))
+            .unwrap(),
+            vec![
+                KeyUsagePurpose::DigitalSignature,
+                KeyUsagePurpose::KeyEncipherment,
+                KeyUsagePurpose::ContentCommitment,
+            ]
+        );
+        assert!(parse_key_usages(&strings(&["sign-everything"])).is_err());
+
+        assert_eq!(
+            parse_extended_key_usages(&strings(&[
+                "serverAuth",
+                "OCSPSigning",
+                "1.3.6.1.4.1.311.20.2.2"
+            ]))
+            .unwrap(),
+            vec![
+                ExtendedKeyUsagePurpose::ServerAuth,
+                ExtendedKeyUsagePurpose::OcspSigning,
+                ExtendedKeyUsagePurpose::Other(vec![1, 3, 6, 1, 4, 1, 311, 20, 2, 2]),
+            ]
+        );
+        assert!(parse_extended_key_usages(&strings(&["5.1.2"])).is_err());
+        assert!(parse_extended_key_usages(&strings(&["web"])).is_err());
+    }
+
+    #[test]
+    fn test_default_usages_per_purpose() {
+        assert_eq!(
+            default_key_usages(Purpose::Server, KeyType::Ecdsa),
+            vec![KeyUsagePurpose::DigitalSignature]
+        );
+        assert_eq!(
+            default_key_usages(Purpose::Client, KeyType::Rsa),
+            vec![
+                KeyUsagePurpose::DigitalSignature,
+                KeyUsagePurpose::KeyEncipherment
+            ]
+        );
+        assert_eq!(
+            default_extended_key_usages(Purpose::CodeSigning),
+            vec![ExtendedKeyUsagePurpose::CodeSigning]
+        );
+    }
+}